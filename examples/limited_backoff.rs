@@ -11,13 +11,11 @@ use tokio::time::{pause, Instant};
 //
 // This means it will wait for 1s, 2s, 3s, 3s, then exit early because the next retry would exceed
 // the overall limit of 10s.
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: Some(Duration::from_secs(10)),
-    backoff_max: Some(Duration::from_secs(3)),
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(5)
+        .t_wait_max(Duration::from_secs(10))
+        .backoff_max(Duration::from_secs(3));
 
 // flavor = "current_thread" is only needed for Tokio's `pause()` functionality to work
 #[tokio::main(flavor = "current_thread")]
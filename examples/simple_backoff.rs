@@ -7,13 +7,8 @@ use tokio::time::{pause, Instant};
 //  doubling the wait with each retry.
 // It specifies no overall limit for retries (t_wait_max), nor a maximum for individual retry waits
 //  (backoff_max).
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 
 // flavor = "current_thread" is only needed for Tokio's `pause()` functionality to work
 #[tokio::main(flavor = "current_thread")]
@@ -0,0 +1,202 @@
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Expr, Ident, LitStr, Token};
+
+/// Arguments given to `#[retry(...)]`.
+///
+/// The configuration and predicate may be given positionally as `#[retry(CONFIG, retry_if)]`, or
+/// by name as `#[retry(config = CONFIG, retry_if = retry_if)]`. Optional settings are always named,
+/// and must follow any positional arguments.
+pub(crate) struct RetryArgs {
+    pub(crate) config: Expr,
+    pub(crate) retry_if: Expr,
+    pub(crate) options: Options,
+}
+
+/// Optional settings, given as `key = value`, as a bare flag like `record_args`, or as a list like
+/// `skip(a, b)`.
+#[derive(Default)]
+pub(crate) struct Options {
+    pub(crate) name: Option<LitStr>,
+    pub(crate) sleeper: Option<Expr>,
+    pub(crate) on_retry: Option<Expr>,
+    pub(crate) on_giveup: Option<Expr>,
+    pub(crate) level: Option<LitStr>,
+    pub(crate) record_args: Option<Ident>,
+    pub(crate) skip: Option<Vec<Ident>>,
+    pub(crate) redact: Option<Vec<Ident>>,
+    pub(crate) report: Option<Ident>,
+    pub(crate) report_failures: Option<Ident>,
+    pub(crate) env: Option<Ident>,
+}
+
+/// Flags that are set by naming them alone.
+const FLAGS: &[&str] = &["record_args", "report", "report_failures", "env"];
+
+/// Settings that take a parenthesized list of identifiers.
+const LISTS: &[&str] = &["skip", "redact"];
+
+const LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+impl Parse for RetryArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut config = None;
+        let mut retry_if = None;
+        let mut options = Options::default();
+        let mut positional = 0;
+        let mut seen_named = false;
+
+        while !input.is_empty() {
+            if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+                seen_named = true;
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+
+                match key.to_string().as_str() {
+                    "config" => set_once(&mut config, &key, parse_operand(input, CONFIG)?)?,
+                    "retry_if" => set_once(&mut retry_if, &key, parse_operand(input, RETRY_IF)?)?,
+                    _ => options.parse_named(key, input)?,
+                }
+            } else if is_keyword(input, FLAGS, |after| {
+                after.is_empty() || after.peek(Token![,])
+            }) {
+                seen_named = true;
+                let key: Ident = input.parse()?;
+                options.set_flag(key)?;
+            } else if is_keyword(input, LISTS, |after| after.peek(syn::token::Paren)) {
+                seen_named = true;
+                let key: Ident = input.parse()?;
+                let content;
+                parenthesized!(content in input);
+                let idents = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                options.set_list(key, idents.into_iter().collect())?;
+            } else {
+                if seen_named {
+                    return Err(
+                        input.error("positional arguments must come before named arguments")
+                    );
+                }
+                match positional {
+                    0 => config = Some(parse_operand(input, CONFIG)?),
+                    1 => retry_if = Some(parse_operand(input, RETRY_IF)?),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            input.parse::<Expr>()?,
+                            "unexpected positional argument, optional settings must be named",
+                        ))
+                    }
+                }
+                positional += 1;
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        let config = config.ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "configuration must be supplied as the first argument or as `config = ...`",
+            )
+        })?;
+        let retry_if = retry_if.ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "retry_if predicate must be supplied as the second argument or as `retry_if = ...`",
+            )
+        })?;
+
+        Ok(RetryArgs {
+            config,
+            retry_if,
+            options,
+        })
+    }
+}
+
+impl Options {
+    fn parse_named(&mut self, key: Ident, input: ParseStream) -> syn::Result<()> {
+        match key.to_string().as_str() {
+            "name" => set_once(&mut self.name, &key, input.parse()?),
+            "sleeper" => set_once(&mut self.sleeper, &key, input.parse()?),
+            "on_retry" => set_once(&mut self.on_retry, &key, input.parse()?),
+            "on_giveup" => set_once(&mut self.on_giveup, &key, input.parse()?),
+            "level" => {
+                let level: LitStr = input.parse()?;
+                if !LEVELS.contains(&level.value().to_lowercase().as_str()) {
+                    return Err(syn::Error::new_spanned(
+                        level,
+                        "expected one of \"trace\", \"debug\", \"info\", \"warn\" or \"error\"",
+                    ));
+                }
+                set_once(&mut self.level, &key, level)
+            }
+            _ => Err(syn::Error::new(
+                key.span(),
+                format!("unknown argument `{key}` for #[retry(...)]"),
+            )),
+        }
+    }
+
+    fn set_flag(&mut self, key: Ident) -> syn::Result<()> {
+        match key.to_string().as_str() {
+            "record_args" => set_once(&mut self.record_args, &key, key.clone()),
+            "report" => set_once(&mut self.report, &key, key.clone()),
+            "report_failures" => set_once(&mut self.report_failures, &key, key.clone()),
+            "env" => set_once(&mut self.env, &key, key.clone()),
+            _ => unreachable!("flags are checked against FLAGS before parsing"),
+        }
+    }
+
+    fn set_list(&mut self, key: Ident, idents: Vec<Ident>) -> syn::Result<()> {
+        match key.to_string().as_str() {
+            "skip" => set_once(&mut self.skip, &key, idents),
+            "redact" => set_once(&mut self.redact, &key, idents),
+            _ => unreachable!("lists are checked against LISTS before parsing"),
+        }
+    }
+}
+
+/// Check if the next argument is one of `keywords`, followed by tokens accepted by `followed_by`.
+fn is_keyword(
+    input: ParseStream,
+    keywords: &[&str],
+    followed_by: impl FnOnce(ParseStream) -> bool,
+) -> bool {
+    let fork = input.fork();
+    match fork.parse::<Ident>() {
+        Ok(ident) => keywords.contains(&ident.to_string().as_str()) && followed_by(&fork),
+        Err(_) => false,
+    }
+}
+
+const CONFIG: &str = "a backoff configuration";
+const RETRY_IF: &str = "a retry_if predicate";
+
+/// Parse the expression for a required argument, rejecting literals since neither argument can be
+/// one.
+fn parse_operand(input: ParseStream, expected: &str) -> syn::Result<Expr> {
+    let expr: Expr = input.parse()?;
+    if let Expr::Lit(lit) = &expr {
+        return Err(syn::Error::new_spanned(
+            lit,
+            format!("expected {expected}, found a literal"),
+        ));
+    }
+    Ok(expr)
+}
+
+/// Store an argument, rejecting it if it was already given positionally or by name.
+fn set_once<T>(slot: &mut Option<T>, key: &Ident, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            key.span(),
+            format!("`{key}` was supplied more than once"),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
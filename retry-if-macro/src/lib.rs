@@ -1,18 +1,30 @@
-use proc_macro2::Ident;
-use quote::quote;
-use syn::parse::Parser;
-use syn::punctuated::Punctuated;
+mod args;
+mod report;
+mod trace;
+
+use args::RetryArgs;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use report::Report;
 use syn::visit_mut;
 use syn::visit_mut::VisitMut;
-use syn::{parse_quote, Expr, ItemFn};
+use syn::{parse_quote_spanned, Expr, ExprAsync, ExprClosure, Item, ItemFn, Signature, Type};
+use trace::Tracing;
 
+/// Redirects the decorated function's own `return`s and `?`s into the retry loop.
+///
+/// Closures, async blocks and nested items have their own control flow, so a `return` or `?`
+/// inside of them is left alone.
+///
+/// Like the rest of the expansion, the generated code is spanned with [Span::mixed_site], so the
+/// `'block` label and match bindings can't collide with labels or variables in the user's code.
 struct BlockModifier;
 
 impl VisitMut for BlockModifier {
     fn visit_expr_mut(&mut self, i: &mut Expr) {
         if let Expr::Try(expr_try) = i {
             let expr = &expr_try.expr;
-            *i = Expr::Match(parse_quote! {
+            *i = Expr::Match(parse_quote_spanned! {Span::mixed_site()=>
                 match #expr {
                     Ok(val) => val,
                     Err(err) => break 'block Err(err.into()),
@@ -20,7 +32,7 @@ impl VisitMut for BlockModifier {
             });
         } else if let Expr::Return(expr_return) = i {
             let return_value = &expr_return.expr;
-            *i = Expr::Break(parse_quote! {
+            *i = Expr::Break(parse_quote_spanned! {Span::mixed_site()=>
                 break 'block #return_value
             });
         }
@@ -28,14 +40,145 @@ impl VisitMut for BlockModifier {
         // Important: continue visiting to find nested expressions
         visit_mut::visit_expr_mut(self, i);
     }
+
+    fn visit_expr_closure_mut(&mut self, _: &mut ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
 }
 
 /// Decorate a function with a given retry configuration.
 ///
 /// Takes two arguments
-/// - `ExponentialBackoffConfig`: type defined in parent crate that configures how to back off
+/// - `ExponentialBackoffConfig`: type defined in parent crate that configures how to back off, or
+///   any other value implementing its `BackoffStrategy` trait
 /// - retry-if: a predicate that takes the same type as the output of the decorated function
 ///
+/// The predicate can also take a `RetryContext` as a second argument, as in
+/// `fn(&T, &RetryContext) -> bool`, which holds the attempt that just finished (starting from 1),
+/// the time elapsed, the delay before the next attempt (if any) and the strategy in use.
+///
+/// In place of a `bool`, the predicate can return a `RetryDecision`, which can override the next
+/// delay with `RetryAfter(Duration)` or `RetryAt(Instant)`. Overridden delays are still capped by
+/// the strategy's `max_delay` (`backoff_max`), and still stop retrying if they'd exceed its
+/// `max_elapsed` (`t_wait_max`).
+///
+/// Both arguments can be any expression, not just identifiers. The configuration can be a path
+/// (`policies::DB_BACKOFF`), a struct literal or a call such as `Self::backoff()`, and the
+/// predicate can be a path to a function or a closure like `|r| r.is_err()`. The predicate is
+/// evaluated after each attempt.
+///
+/// The configuration is evaluated once at the start of each call, and that value is used for the
+/// whole call. It can come from anywhere the function can reach at runtime: a field such as
+/// `self.retry_config`, an argument, or a `LazyLock` or `OnceLock` static such as
+/// `*RETRY_CONFIG` or `RETRY_CONFIG.get_or_init(load_config)`. Since the value is moved out of
+/// the expression, a field or static whose type isn't `Copy` should be borrowed, as in
+/// `&self.retry_config`.
+///
+/// # Named Arguments
+/// Arguments can also be given by name, in any order, after any positional arguments:
+/// - `config = ...`: the backoff configuration
+/// - `retry_if = ...`: the retry predicate
+/// - `name = "..."`: the name reported in traces, defaulting to the name of the function
+/// - `sleeper = ...`: the `Sleeper` async functions wait with, defaulting to tokio within a
+///   tokio runtime, and otherwise to async-std or smol if enabled
+/// - `on_retry = ...`: a hook run before each sleep, see [Hooks](#hooks)
+/// - `on_giveup = ...`: a hook run when retries run out, see [Hooks](#hooks)
+/// - `report`: also generate a `<fn>_with_report` companion, see [Reports](#reports)
+/// - `report_failures`: include the `Debug` rendering of each retried result in the report
+/// - `level = "..."`: the level of the spans and events emitted with the `tracing` feature, one of
+///   `"trace"`, `"debug"`, `"info"` (the default), `"warn"` or `"error"`
+/// - `env`: override the configuration from environment variables, see
+///   [Environment Overrides](#environment-overrides)
+/// - `record_args`: record the function's arguments on its `retry` span with their `Debug`
+///   implementations
+/// - `skip(a, b)`: leave out the given arguments when recording arguments
+/// - `redact(a, b)`: record the given arguments as `"<redacted>"` rather than their values
+///
+/// ```no_run
+/// #[retry(config = BACKOFF_CONFIG, retry_if = |r| r.is_err(), name = "fetch_user")]
+/// async fn fetch_user(id: u64) -> Result<User, Error> {
+///     // ...
+/// }
+/// ```
+///
+/// # Hooks
+/// `on_retry` is called with the failed result and a `RetryContext` once `retry_if` has decided to
+/// retry, and before sleeping. It isn't called after the final attempt. Async functions await it,
+/// so it takes an `async fn` or an async closure, while synchronous functions take any function or
+/// closure.
+///
+/// `on_giveup` is called once when `max_retries` or `t_wait_max` ends the retries while `retry_if`
+/// would still retry the result. It's given the final result, and a `GiveUpContext` with the number
+/// of attempts, the time elapsed and the `StopReason`.
+///
+/// ```no_run
+/// #[retry(BACKOFF_CONFIG, |r| r.is_err(), on_retry = async |_, _| self.refresh_token().await)]
+/// async fn request(&self) -> Result<Response, Error> {
+///     // ...
+/// }
+/// ```
+///
+/// # Reports
+/// With `report`, a companion function named `<fn>_with_report` is generated next to the decorated
+/// function. It takes the same arguments and retries in the same way, but returns
+/// `(T, RetryReport)`, where the report holds the number of attempts, each delay, the duration of
+/// each attempt, the total time elapsed and the `StopReason`. With `report_failures` as well, the
+/// report includes the `Debug` rendering of every result that was retried, which requires the
+/// return type to implement `Debug`.
+///
+/// Since the companion is an extra method, `report` can't be used in trait implementations.
+///
+/// ```no_run
+/// #[retry(BACKOFF_CONFIG, |r| r.is_err(), report)]
+/// async fn fetch_user(id: u64) -> Result<User, Error> {
+///     // ...
+/// }
+///
+/// let (user, report) = fetch_user_with_report(1).await;
+/// metrics::histogram!("fetch_user.attempts").record(report.attempts);
+/// ```
+///
+/// # Environment Overrides
+/// With `env`, each field of the configuration can be overridden by an environment variable named
+/// `RETRY_IF__<FN>__<FIELD>`, where `<FN>` is the function's name in upper case, such as
+/// `RETRY_IF__FETCH_USER__MAX_RETRIES=10` or `RETRY_IF__FETCH_USER__T_WAIT=250ms`. The variables
+/// are read at runtime, as `ExponentialBackoffConfig::with_env` reads them, so the configuration
+/// must be an `ExponentialBackoffConfig`. If they can't be parsed, or the configuration they give
+/// fails validation, the configuration is used as written, with a warning under the `tracing`
+/// feature.
+///
+/// ```no_run
+/// #[retry(BACKOFF_CONFIG, |r| r.is_err(), env)]
+/// async fn fetch_user(id: u64) -> Result<User, Error> {
+///     // ...
+/// }
+/// ```
+///
+/// # Tracing
+/// With the `tracing` feature enabled, each call is wrapped in a `retry` span, and each attempt in
+/// an `attempt` span recording its number (from 1), the time elapsed when it began, and the delay
+/// chosen after it. An event is emitted before each sleep, and once more when the loop stops,
+/// recording the number of attempts, the elapsed time and the `StopReason`.
+///
+/// ```no_run
+/// #[retry(BACKOFF_CONFIG, |r| r.is_err(), level = "debug", record_args, redact(token))]
+/// async fn fetch_user(id: u64, token: &str) -> Result<User, Error> {
+///     // ...
+/// }
+/// ```
+///
+/// # Synchronous Functions
+/// Functions that aren't `async` can be decorated as well. They follow the same
+/// `ExponentialBackoffConfig` semantics, but block the current thread with `std::thread::sleep`
+/// between attempts, so they shouldn't be called from within an async runtime.
+///
+/// # Without the Attribute
+/// The loop an async function expands to is also available as the `retry_if::retry` function,
+/// and through the `RetryExt` trait on async closures, for operations that aren't functions of
+/// their own.
+///
 /// # Example: Retrying a Result-producing Function on Err(...)
 /// The below example sets up a basic retry configuration that will retry up to five times, waiting
 /// at first 1 second, then 2 seconds, 4 seconds, etc.
@@ -44,13 +187,8 @@ impl VisitMut for BlockModifier {
 /// maximum waiting time on each backoff (backoff_max).
 ///
 /// ```no_run
-/// const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-///     max_retries: 5,
-///     t_wait: Duration::from_secs(1),
-///     backoff: 2.0,
-///     t_wait_max: None,
-///     backoff_max: None,
-/// };
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 ///
 /// // this takes an address of the same type as the output of the decorated function
 /// //  it returns a boolean specifying if the function should be retried based on the result
@@ -69,77 +207,270 @@ pub fn retry(
     args: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let comma_punctuated = Punctuated::<Ident, syn::Token![,]>::parse_separated_nonempty;
-    let punctuated_args = comma_punctuated.parse(args).expect(
-        "retry macro requires arguments of ExponentialBackoffConfig and 'retry-if' function",
-    );
-    let mut punctuated_args_iter = punctuated_args.iter();
+    let item = proc_macro2::TokenStream::from(item);
 
-    let config = punctuated_args_iter
-        .next()
-        .expect("configuration must be supplied as an argument to #[retry(...)]");
+    let args = syn::parse::<RetryArgs>(args);
+    let impl_fn = syn::parse2::<ItemFn>(item.clone())
+        .map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "#[retry(...)] can only be applied to functions and methods",
+            )
+        })
+        .and_then(|impl_fn| check_signature(&impl_fn).map(|_| impl_fn));
 
-    let retry_if = punctuated_args_iter
-        .next()
-        .expect("retry_if predicate must be supplied as the second argument to #[retry(...)]");
+    let decorated = match (args, impl_fn) {
+        (Ok(args), Ok(impl_fn)) => decorate_fn(impl_fn, &args),
+        (Err(mut err), Err(fn_err)) => {
+            err.combine(fn_err);
+            Err(err)
+        }
+        (Err(err), _) | (_, Err(err)) => Err(err),
+    };
 
-    let parsed: ItemFn =
-        syn::parse(item).expect("failed to parse item under #[retry(...)] as function");
+    match decorated {
+        Ok(decorated) => decorated.into(),
+        Err(err) => {
+            let errors = err.to_compile_error();
 
-    decorate_fn(parsed, config, retry_if)
+            // emit the item untouched alongside the errors, so they aren't buried under unrelated
+            //  errors from code that refers to it
+            (quote! {
+                #errors
+                #item
+            })
+            .into()
+        }
+    }
+}
+
+/// Check that the function under `#[retry(...)]` can be called repeatedly in a retry loop.
+///
+/// Every problem found is reported, each pointing at the offending part of the signature.
+fn check_signature(impl_fn: &ItemFn) -> syn::Result<()> {
+    let sig = &impl_fn.sig;
+    let mut errors = Vec::new();
+
+    if let Some(receiver) = sig.receiver() {
+        let consumes_self = receiver.reference.is_none()
+            && matches!(&*receiver.ty, Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("Self"));
+
+        if consumes_self {
+            errors.push(syn::Error::new_spanned(
+                receiver,
+                "#[retry(...)] cannot be applied to methods that consume `self`, since `self` is \
+                no longer available to retry with after the first attempt",
+            ));
+        }
+    }
+
+    errors
+        .into_iter()
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        })
+        .map_or(Ok(()), Err)
 }
 
 /// Wrap the underlying implementation with a retry.
 ///
-/// This takes the underlying function as [ItemFn], the backoff configuration (defined in parent
-/// crate) as an `&Ident`, and the `&Ident` for the retry function
-fn decorate_fn(mut impl_fn: ItemFn, config: &Ident, retry_if: &Ident) -> proc_macro::TokenStream {
+/// This takes the underlying function as [ItemFn] and the parsed [RetryArgs], which hold the
+/// backoff configuration (defined in parent crate), the retry function and any optional settings.
+fn decorate_fn(mut impl_fn: ItemFn, args: &RetryArgs) -> syn::Result<TokenStream> {
     let attrs = &impl_fn.attrs;
     let vis = &impl_fn.vis;
     let sig = &impl_fn.sig;
+    let retry_if = &args.retry_if;
+    let name = match &args.options.name {
+        Some(name) => name.value(),
+        None => sig.ident.to_string(),
+    };
+
+    // every identifier and label introduced below is resolved at the macro's definition site, so
+    //  the decorated body behaves exactly as it would without the attribute
+    let span = Span::mixed_site();
+
+    // with `env`, the configuration is overridden from `RETRY_IF__<FN>__*` wherever it's read
+    let config = &args.config;
+    let config = match &args.options.env {
+        Some(_) => {
+            let ident = sig.ident.to_string();
+            let prefix = format!(
+                "RETRY_IF__{}_",
+                ident.trim_start_matches("r#").to_uppercase()
+            );
+            quote_spanned! {span=> ::retry_if::__private::with_env(#config, #prefix) }
+        }
+        None => quote! { #config },
+    };
+
+    // async functions sleep on the runtime through a `Sleeper`, while synchronous functions block
+    //  the current thread
+    let (setup, now, sleep) = if sig.asyncness.is_some() {
+        let sleeper = match &args.options.sleeper {
+            Some(sleeper) => quote! { #sleeper },
+            None => quote_spanned! {span=> ::retry_if::__private::DefaultSleeper },
+        };
+        (
+            quote_spanned! {span=> let sleeper = #sleeper; },
+            quote_spanned! {span=> ::retry_if::Sleeper::now(&sleeper) },
+            quote_spanned! {span=> ::retry_if::Sleeper::sleep(&sleeper, retry_wait).await },
+        )
+    } else {
+        if let Some(sleeper) = &args.options.sleeper {
+            return Err(syn::Error::new_spanned(
+                sleeper,
+                "`sleeper` only applies to async functions, synchronous functions always block \
+                the current thread",
+            ));
+        }
+        (
+            quote! {},
+            quote_spanned! {span=> ::std::time::Instant::now() },
+            quote_spanned! {span=> ::std::thread::sleep(retry_wait) },
+        )
+    };
+
+    let is_async = sig.asyncness.is_some();
+    let should_retry = should_retry(retry_if);
+
+    // `on_retry` runs once a retry is certain, so it never sees the final result, which is
+    //  `on_giveup`'s alone when it's still one to retry
+    let on_retry = args.options.on_retry.as_ref().map(|on_retry| {
+        // rebuilt, since the wait may no longer be the computed delay the predicate was given
+        let context = quote_spanned! {span=> state.retry_context(retry_wait) };
+        let hook = quote_spanned! {span=> ::retry_if::__private::retry_hook };
+        call_hook(on_retry, hook, context, is_async)
+    });
+    let on_giveup = args.options.on_giveup.as_ref().map(|on_giveup| {
+        let context = quote_spanned! {span=> state.give_up_context(#now, reason) };
+        let hook = quote_spanned! {span=> ::retry_if::__private::give_up_hook };
+        let on_giveup = call_hook(on_giveup, hook, context, is_async);
+        quote_spanned! {span=>
+            if reason != ::retry_if::StopReason::NotRetried {
+                #on_giveup
+            }
+        }
+    });
+
+    let tracing = Tracing::new(&args.options, sig, name)?;
+    let call_span = tracing.call_span();
+    let attempt_span = tracing.attempt_span(&now);
+    let sleep_event = tracing.sleep_event();
+    let stop_event = tracing.stop_event(&now);
 
     (BlockModifier {}).visit_block_mut(&mut impl_fn.block);
     let block = &impl_fn.block;
+    let attempt = tracing.instrument_attempt(quote_spanned! {span=> 'block: #block }, is_async);
 
-    (quote! {
-        #(#attrs)*
-        #vis #sig {
-            let __start = tokio::time::Instant::now();
-            let __backoff_max = #config.backoff_max.unwrap_or(std::time::Duration::MAX);
-            let mut __attempt = 0;
-
-            loop {
-                let result = 'block: {
-                    #block
-                };
-
-                // Return result if retry isn't required, or if we ran out of attempts
-                if !#retry_if(&result) || __attempt >= #config.max_retries {
-                    return result;
-                }
+    // the `_with_report` companion runs the same loop, only keeping track of it as it goes
+    let expand = |sig: &Signature, report: &Report| {
+        let report_setup = report.setup();
+        let attempt_started = report.attempt_started(&now);
+        let attempt_finished = report.attempt_finished(&now);
+        let retrying = report.retrying();
+        let output = report.output(&now);
 
-                let retry_wait = #config.t_wait
-                    .mul_f64(#config.backoff.powi(__attempt))
-                    .min(__backoff_max);
+        let body = tracing.instrument_call(
+            quote_spanned! {span=>
+                #setup
+                #report_setup
+                // read once, so a configuration that changes mid-call can't mix old and new values
+                let mut state = ::retry_if::__private::RetryLoop::new(#config, #now);
 
-                __attempt += 1;
+                loop {
+                    #attempt_span
+                    #attempt_started
 
-                if let Some(max_wait) = #config.t_wait_max {
-                    let now = tokio::time::Instant::now();
-                    let since_start = now - __start;
+                    #[allow(clippy::diverging_sub_expression)]
+                    let result = #attempt;
+                    #attempt_finished
 
-                    // Return if our overall duration is going to exceed `max_wait`
-                    if since_start + retry_wait > max_wait {
-                        return result;
-                    }
-                }
+                    // Return result if retry isn't required, or if we ran out of attempts or time
+                    let retry_wait = match state.next_wait(&result, #now, |result, context| {
+                        #should_retry
+                    }) {
+                        Ok(retry_wait) => retry_wait,
+                        Err(reason) => {
+                            #stop_event
+                            #on_giveup
+                            return #output;
+                        }
+                    };
 
-                if cfg!(feature = "tracing") {
-                    tracing::info!("Sleeping {retry_wait:?} on attempt {__attempt}");
+                    #sleep_event
+                    #retrying
+                    #on_retry
+                    state.retrying();
+                    #sleep;
                 }
-                tokio::time::sleep(retry_wait).await;
+            },
+            is_async,
+        );
+
+        quote_spanned! {span=>
+            #sig {
+                #call_span
+                #body
             }
         }
+    };
+
+    let decorated = expand(sig, &Report::disabled());
+    let companion = Report::companion(&args.options)?.map(|report| {
+        let doc = format!(
+            "Call `{}`, returning a `RetryReport` of its retries along with its result.",
+            sig.ident
+        );
+        let attrs = attrs.iter().filter(|attr| !attr.path().is_ident("doc"));
+        let companion = expand(&Report::signature(sig), &report);
+
+        quote_spanned! {span=>
+            #[doc = #doc]
+            #(#attrs)*
+            #vis #companion
+        }
+    });
+
+    Ok(quote_spanned! {span=>
+        #(#attrs)*
+        #vis #decorated
+
+        #companion
     })
-    .into()
+}
+
+/// Evaluate the `retry_if` predicate against `result` and its `context`.
+///
+/// Closures written in place are called according to their number of parameters, since they can't
+/// infer their signature otherwise, while other predicates pick their signature through a trait.
+fn should_retry(retry_if: &Expr) -> TokenStream {
+    let span = Span::mixed_site();
+    match retry_if {
+        Expr::Closure(closure) if closure.inputs.len() == 2 => quote_spanned! {span=>
+            ::retry_if::__private::should_retry_with_context(#retry_if, result, context)
+        },
+        Expr::Closure(_) => quote_spanned! {span=>
+            ::retry_if::__private::should_retry(#retry_if, result)
+        },
+        _ => quote_spanned! {span=>
+            ::retry_if::__private::Predicate::should_retry(&#retry_if, result, context)
+        },
+    }
+}
+
+/// Call a hook with a reference to the result and to the `context` it's given, through `call` for
+/// async functions.
+fn call_hook(hook: &Expr, call: TokenStream, context: TokenStream, is_async: bool) -> TokenStream {
+    let span = Span::mixed_site();
+    if is_async {
+        quote_spanned! {span=>
+            #call(#hook, &result, &#context).await;
+        }
+    } else {
+        quote_spanned! {span=>
+            ::retry_if::__private::hook_blocking(#hook, &result, &#context);
+        }
+    }
 }
@@ -0,0 +1,113 @@
+use crate::args::Options;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_quote, ReturnType, Signature};
+
+/// The bookkeeping added to the `<fn>_with_report` companion generated with `report`, which
+/// returns a `RetryReport` alongside the result.
+///
+/// The decorated function itself is expanded with a disabled `Report`, where every method produces
+/// no tokens and the loop returns the result alone.
+pub(crate) struct Report {
+    enabled: bool,
+    failures: bool,
+}
+
+impl Report {
+    pub(crate) fn disabled() -> Self {
+        Report {
+            enabled: false,
+            failures: false,
+        }
+    }
+
+    /// The report for the companion, if `report` was given.
+    pub(crate) fn companion(options: &Options) -> syn::Result<Option<Self>> {
+        match (&options.report, &options.report_failures) {
+            (None, Some(report_failures)) => Err(syn::Error::new_spanned(
+                report_failures,
+                "`report_failures` has no effect without `report`",
+            )),
+            (None, None) => Ok(None),
+            (Some(_), report_failures) => Ok(Some(Report {
+                enabled: true,
+                failures: report_failures.is_some(),
+            })),
+        }
+    }
+
+    /// The signature of the companion, named `<fn>_with_report` and returning `(T, RetryReport)`.
+    pub(crate) fn signature(sig: &Signature) -> Signature {
+        let output = match &sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! { #ty },
+        };
+
+        let mut sig = sig.clone();
+        sig.ident = format_ident!("{}_with_report", sig.ident);
+        sig.output = parse_quote! { -> (#output, ::retry_if::RetryReport) };
+        sig
+    }
+
+    /// Start the report, binding it to `report`.
+    pub(crate) fn setup(&self) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+        let failures = self.failures;
+
+        quote_spanned! {Span::mixed_site()=>
+            let mut report = ::retry_if::__private::ReportBuilder::new(#failures);
+        }
+    }
+
+    /// Note when an attempt starts, binding it to `attempt_start`.
+    pub(crate) fn attempt_started(&self, now: &TokenStream) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            let attempt_start = #now;
+        }
+    }
+
+    /// Record how long the attempt that just finished took.
+    pub(crate) fn attempt_finished(&self, now: &TokenStream) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            report.attempt(#now - attempt_start);
+        }
+    }
+
+    /// Record the delay before the next attempt, and the result that's being retried.
+    pub(crate) fn retrying(&self) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+        let failure = self.failures.then(|| {
+            quote_spanned! {Span::mixed_site()=>
+                report.failure(&result);
+            }
+        });
+
+        quote_spanned! {Span::mixed_site()=>
+            report.retry(retry_wait);
+            #failure
+        }
+    }
+
+    /// The value returned once retrying stops for `reason`.
+    pub(crate) fn output(&self, now: &TokenStream) -> TokenStream {
+        if !self.enabled {
+            return quote_spanned! {Span::mixed_site()=> result };
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            (result, report.finish(reason, state.elapsed(#now)))
+        }
+    }
+}
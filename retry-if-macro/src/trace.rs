@@ -0,0 +1,190 @@
+use crate::args::Options;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{FnArg, Ident, Pat, Signature};
+
+/// The spans and events added to the expansion when retry-if's `tracing` feature is enabled.
+///
+/// Each call is wrapped in a `retry` span, which records the arguments opted into with
+/// `record_args`. The attempt spans and events within it are emitted by retry-if's `Trace`, which
+/// `Retry` emits them with as well.
+///
+/// When the feature is disabled, every method produces no tokens.
+pub(crate) struct Tracing {
+    enabled: bool,
+    level: TokenStream,
+    name: String,
+    fields: Vec<TokenStream>,
+}
+
+impl Tracing {
+    pub(crate) fn new(options: &Options, sig: &Signature, name: String) -> syn::Result<Self> {
+        let level = match &options.level {
+            Some(level) => format_ident!("{}", level.value().to_uppercase(), span = level.span()),
+            None => format_ident!("INFO"),
+        };
+
+        Ok(Tracing {
+            enabled: cfg!(feature = "tracing"),
+            level: quote_spanned! {Span::mixed_site()=>
+                ::retry_if::__private::tracing::Level::#level
+            },
+            name,
+            fields: argument_fields(options, sig)?,
+        })
+    }
+
+    /// Create the span for the whole call, binding it to `call_span`, and the `Trace` emitting
+    /// the spans and events within it, binding it to `trace`.
+    pub(crate) fn call_span(&self) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+        let Tracing {
+            level,
+            name,
+            fields,
+            ..
+        } = self;
+
+        quote_spanned! {Span::mixed_site()=>
+            let call_span = ::retry_if::__private::tracing::span!(
+                #level,
+                "retry",
+                name = #name
+                #(, #fields)*
+            );
+            let trace = ::retry_if::__private::Trace::new(#name, #level);
+        }
+    }
+
+    /// Create the span for the attempt about to be made, binding it to `attempt_span`.
+    pub(crate) fn attempt_span(&self, now: &TokenStream) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            let attempt_span = trace.attempt_span(&state, #now);
+        }
+    }
+
+    /// Run the attempt in `block` within its span.
+    ///
+    /// Async attempts are instrumented as a future, since entering a span across an `.await` would
+    /// attribute unrelated work to it.
+    pub(crate) fn instrument_attempt(&self, block: TokenStream, is_async: bool) -> TokenStream {
+        if !self.enabled {
+            block
+        } else if is_async {
+            quote_spanned! {Span::mixed_site()=>
+                ::retry_if::__private::tracing::Instrument::instrument(
+                    async { #block },
+                    attempt_span.clone(),
+                )
+                .await
+            }
+        } else {
+            quote_spanned! {Span::mixed_site()=>
+                {
+                    let _attempt_guard = attempt_span.enter();
+                    #block
+                }
+            }
+        }
+    }
+
+    /// Record the delay before the next attempt, and log that the function is sleeping.
+    pub(crate) fn sleep_event(&self) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            trace.sleeping(&attempt_span, &state, retry_wait);
+        }
+    }
+
+    /// Log why the loop stopped, given the `reason` it stopped.
+    pub(crate) fn stop_event(&self, now: &TokenStream) -> TokenStream {
+        if !self.enabled {
+            return quote! {};
+        }
+
+        quote_spanned! {Span::mixed_site()=>
+            trace.stopped(&state, #now, reason);
+        }
+    }
+
+    /// Wrap the whole retry loop in the call span.
+    pub(crate) fn instrument_call(&self, body: TokenStream, is_async: bool) -> TokenStream {
+        if !self.enabled {
+            body
+        } else if is_async {
+            quote_spanned! {Span::mixed_site()=>
+                ::retry_if::__private::tracing::Instrument::instrument(
+                    async move { #body },
+                    call_span,
+                )
+                .await
+            }
+        } else {
+            quote_spanned! {Span::mixed_site()=>
+                let _call_guard = call_span.enter();
+                #body
+            }
+        }
+    }
+}
+
+/// Span fields for the arguments opted into with `record_args`, less those in `skip(...)`.
+///
+/// Arguments are recorded with their `Debug` implementation, except for those in `redact(...)`.
+/// Receivers and arguments bound to patterns other than a plain identifier are never recorded.
+fn argument_fields(options: &Options, sig: &Signature) -> syn::Result<Vec<TokenStream>> {
+    let arguments: Vec<&Ident> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let skip = options.skip.as_deref().unwrap_or_default();
+    let redact = options.redact.as_deref().unwrap_or_default();
+
+    for ident in skip.iter().chain(redact) {
+        if options.record_args.is_none() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "`skip` and `redact` have no effect without `record_args`",
+            ));
+        }
+        if !arguments.contains(&ident) {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!("`{ident}` is not an argument of `{}`", sig.ident),
+            ));
+        }
+    }
+
+    if options.record_args.is_none() {
+        return Ok(Vec::new());
+    }
+
+    Ok(arguments
+        .into_iter()
+        .filter(|ident| !skip.contains(ident))
+        .map(|ident| {
+            if redact.contains(ident) {
+                quote! { #ident = "<redacted>" }
+            } else {
+                quote! { #ident = ?#ident }
+            }
+        })
+        .collect())
+}
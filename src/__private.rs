@@ -0,0 +1,214 @@
+//! Support items for code generated by `#[retry(...)]`. Not public API.
+use crate::{
+    BackoffStrategy, ExponentialBackoffConfig, GiveUpContext, RetryContext, RetryDecision,
+    RetryReport, StopReason,
+};
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// Decorated code logs through this re-export, so it doesn't depend on the features or
+/// dependencies of the crate it's expanded in.
+#[cfg(feature = "tracing")]
+pub use tracing;
+
+/// The sleeper used by async functions when `#[retry(...)]` isn't given one.
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+pub use crate::sleeper::DefaultSleeper;
+
+/// Emits the spans and events of a call, at the level it's given at runtime.
+#[cfg(feature = "tracing")]
+pub use crate::trace::Trace;
+
+/// Jitters the delays of a call, kept for the length of the call.
+pub use crate::jitter::JitterState;
+
+/// Decides whether and when to retry each attempt of a call.
+pub use crate::retry_loop::RetryLoop;
+
+/// Override the configuration of a function decorated with `env` from the environment, keeping
+/// the configuration it was compiled with if the environment can't be read.
+pub fn with_env(config: ExponentialBackoffConfig, prefix: &str) -> ExponentialBackoffConfig {
+    match config.with_env(prefix) {
+        Ok(config) => config,
+        Err(_error) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Ignoring {prefix}_* overrides: {_error}");
+            config
+        }
+    }
+}
+
+/// Evaluate a `retry_if` closure that takes only the result of an attempt.
+///
+/// Routing the call through a generic function gives closures such as `|r| r.is_err()` the
+/// expected signature, which they can't infer when called in place.
+#[inline]
+pub fn should_retry<T, D, F>(retry_if: F, result: &T) -> RetryDecision
+where
+    F: Fn(&T) -> D,
+    D: Into<RetryDecision>,
+{
+    retry_if(result).into()
+}
+
+/// Evaluate a `retry_if` closure that takes the result of an attempt and its [RetryContext].
+#[inline]
+pub fn should_retry_with_context<T, D, F>(
+    retry_if: F,
+    result: &T,
+    context: &RetryContext,
+) -> RetryDecision
+where
+    F: Fn(&T, &RetryContext) -> D,
+    D: Into<RetryDecision>,
+{
+    retry_if(result, context).into()
+}
+
+/// A `retry_if` predicate given as a path or other expression, which may or may not take a
+/// [RetryContext], and may return a `bool` or a [RetryDecision].
+///
+/// The `Args` marker keeps the implementations apart, and is inferred from whichever signature the
+/// predicate has. Closures written in place go through [should_retry] or
+/// [should_retry_with_context] instead, chosen by their number of parameters, since they can only
+/// infer their signature from a single `Fn` bound.
+pub trait Predicate<T, Args> {
+    fn should_retry(&self, result: &T, context: &RetryContext) -> RetryDecision;
+}
+
+/// Marks predicates taking only the result.
+pub struct ResultOnly;
+
+/// Marks predicates taking the result and a [RetryContext].
+pub struct WithContext;
+
+impl<T, D, F> Predicate<T, (ResultOnly, D)> for F
+where
+    F: Fn(&T) -> D,
+    D: Into<RetryDecision>,
+{
+    #[inline]
+    fn should_retry(&self, result: &T, _context: &RetryContext) -> RetryDecision {
+        self(result).into()
+    }
+}
+
+impl<T, D, F> Predicate<T, (WithContext, D)> for F
+where
+    F: Fn(&T, &RetryContext) -> D,
+    D: Into<RetryDecision>,
+{
+    #[inline]
+    fn should_retry(&self, result: &T, context: &RetryContext) -> RetryDecision {
+        self(result, context).into()
+    }
+}
+
+/// Decide how long to wait before the next attempt, or why to stop retrying instead.
+///
+/// `delay` is what `strategy` would wait after the attempt, and `since_start` the time elapsed at
+/// `now`.
+#[inline]
+pub fn next_wait<S: BackoffStrategy + ?Sized>(
+    strategy: &S,
+    decision: RetryDecision,
+    delay: Option<Duration>,
+    now: Instant,
+    since_start: Duration,
+) -> Result<Duration, StopReason> {
+    if decision == RetryDecision::Stop {
+        return Err(StopReason::NotRetried);
+    }
+    let Some(delay) = delay else {
+        return Err(StopReason::RetriesExhausted);
+    };
+
+    // the predicate may ask for a different wait than the computed one
+    let max_delay = strategy.max_delay().unwrap_or(Duration::MAX);
+    let wait = decision.delay(delay, now, max_delay).min(max_delay);
+
+    match strategy.max_elapsed() {
+        // Stop if our overall duration is going to exceed `max_elapsed`
+        Some(max_elapsed)
+            if since_start
+                .checked_add(wait)
+                .is_none_or(|t| t > max_elapsed) =>
+        {
+            Err(StopReason::DeadlineExceeded)
+        }
+        _ => Ok(wait),
+    }
+}
+
+/// Run an async function's `on_retry` hook.
+///
+/// Taking an [AsyncFnOnce] lets both `async fn`s and async closures borrow the result and context
+/// they're given. Naming the context's type here has closures accept it with any lifetime, since
+/// one inferred for a single lifetime would keep the function's future from being `Send`.
+#[inline]
+pub async fn retry_hook<T, F>(hook: F, result: &T, context: &RetryContext<'_>)
+where
+    F: AsyncFnOnce(&T, &RetryContext<'_>),
+{
+    hook(result, context).await
+}
+
+/// Run an async function's `on_giveup` hook.
+#[inline]
+pub async fn give_up_hook<T, F>(hook: F, result: &T, context: &GiveUpContext)
+where
+    F: AsyncFnOnce(&T, &GiveUpContext),
+{
+    hook(result, context).await
+}
+
+/// Run a synchronous function's `on_retry` or `on_giveup` hook.
+#[inline]
+pub fn hook_blocking<T, C, F>(hook: F, result: &T, context: &C)
+where
+    F: FnOnce(&T, &C),
+{
+    hook(result, context)
+}
+
+/// Builds the [RetryReport] returned by a `_with_report` companion as its retry loop runs.
+pub struct ReportBuilder(RetryReport);
+
+impl ReportBuilder {
+    #[inline]
+    pub fn new(record_failures: bool) -> Self {
+        ReportBuilder(RetryReport::new(record_failures))
+    }
+
+    #[inline]
+    pub fn attempt(&mut self, duration: Duration) {
+        self.0.attempts += 1;
+        self.0.attempt_durations.push(duration);
+    }
+
+    #[inline]
+    pub fn retry(&mut self, delay: Duration) {
+        self.0.delays.push(delay);
+    }
+
+    #[inline]
+    pub fn failure<T: Debug>(&mut self, result: &T) {
+        self.rendered_failure(format!("{result:?}"));
+    }
+
+    #[inline]
+    pub fn rendered_failure(&mut self, failure: String) {
+        if let Some(failures) = &mut self.0.failures {
+            failures.push(failure);
+        }
+    }
+
+    #[inline]
+    pub fn finish(self, reason: StopReason, elapsed: Duration) -> RetryReport {
+        RetryReport {
+            reason,
+            elapsed,
+            ..self.0
+        }
+    }
+}
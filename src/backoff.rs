@@ -0,0 +1,99 @@
+use crate::Jitter;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long to wait between attempts, and when to stop retrying.
+///
+/// `#[retry(...)]` accepts any value implementing this as its configuration, with
+/// [ExponentialBackoffConfig](crate::ExponentialBackoffConfig) being the one built in.
+///
+/// Delays requested by a `retry_if` predicate through a
+/// [RetryDecision](crate::RetryDecision) are capped by [BackoffStrategy::max_delay], and retrying
+/// stops before any wait that would take longer than [BackoffStrategy::max_elapsed] in total.
+///
+/// Strategies must be `Send` and `Sync` to be retried with, since the
+/// [RetryContext](crate::RetryContext) given to predicates and hooks borrows them, and async
+/// functions must stay `Send` while a hook holds it.
+///
+/// # Example: Constant Delay
+/// ```
+/// # use retry_if::BackoffStrategy;
+/// # use std::time::Duration;
+/// #[derive(Debug)]
+/// struct Constant {
+///     delay: Duration,
+///     retries: u32,
+/// }
+///
+/// impl BackoffStrategy for Constant {
+///     fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+///         (attempt <= self.retries).then_some(self.delay)
+///     }
+/// }
+/// ```
+pub trait BackoffStrategy {
+    /// The delay before retrying after `attempt` failed, where the initial call is attempt 1, and
+    /// `elapsed` is the time since the initial call.
+    ///
+    /// Returning `None` stops retrying, which is reported as
+    /// [StopReason::RetriesExhausted](crate::StopReason::RetriesExhausted).
+    fn delay(&self, attempt: u32, elapsed: Duration) -> Option<Duration>;
+
+    /// The longest any single wait may be, including waits requested by a `retry_if` predicate.
+    fn max_delay(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The longest time to spend retrying, where retrying stops rather than starting a wait that
+    /// would exceed it.
+    fn max_elapsed(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The randomization applied to each delay, if any.
+    fn jitter_mode(&self) -> Option<Jitter> {
+        None
+    }
+
+    /// The seed for the jitter's random numbers, which makes every call wait the same delays.
+    ///
+    /// Without a seed, each call is seeded randomly.
+    fn rng_seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// The strategy to read for the decision after one attempt, for strategies that can change
+    /// while they're in use, such as [SharedBackoff](crate::SharedBackoff).
+    ///
+    /// The delay, jitter and limits of each decision are all read from the snapshot, so a change
+    /// can't mix old and new values within a decision. `None` reads them from `self`.
+    fn snapshot(&self) -> Option<Arc<dyn BackoffStrategy + Send + Sync>> {
+        None
+    }
+}
+
+impl<S: BackoffStrategy + ?Sized> BackoffStrategy for &S {
+    fn delay(&self, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        (**self).delay(attempt, elapsed)
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        (**self).max_delay()
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        (**self).max_elapsed()
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        (**self).jitter_mode()
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        (**self).rng_seed()
+    }
+
+    fn snapshot(&self) -> Option<Arc<dyn BackoffStrategy + Send + Sync>> {
+        (**self).snapshot()
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{BackoffStrategy, ConfigError, Jitter};
 use std::time::Duration;
 
 /// Configuration for an exponential backoff, allowing control over the entire strategy.
@@ -11,6 +12,8 @@ use std::time::Duration;
 /// however possible for the execution to exceed `t_wait_max` if the decorated code
 /// (e.g. calling an API) causes it to exceed this time.
 ///
+/// This is the [BackoffStrategy] built into retry-if, where `backoff_max` is its `max_delay` and
+/// `t_wait_max` its `max_elapsed`.
 ///
 /// # Example: Classic Exponential Backoff
 /// This backoff configuration will retry up to 5 times, waiting 1 second at first, then 2 seconds,
@@ -19,13 +22,8 @@ use std::time::Duration;
 /// # use crate::retry_if::ExponentialBackoffConfig;
 /// # use tokio::time::Duration;
 ///
-/// const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-///     max_retries: 5,
-///     t_wait: Duration::from_secs(1),
-///     backoff: 2.0,
-///     t_wait_max: None,
-///     backoff_max: None,
-/// };
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 /// ```
 ///
 /// # Example: Constant Linear Retries
@@ -35,13 +33,10 @@ use std::time::Duration;
 /// # use crate::retry_if::ExponentialBackoffConfig;
 /// # use tokio::time::Duration;
 ///
-/// const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-///     max_retries: 5,
-///     t_wait: Duration::from_secs(1),
-///     backoff: 1.0,
-///     t_wait_max: None,
-///     backoff_max: None,
-/// };
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1))
+///         .max_retries(5)
+///         .backoff(1.0);
 /// ```
 ///
 /// # Example: Backoff With a Maximum Wait Time
@@ -54,13 +49,23 @@ use std::time::Duration;
 /// # use crate::retry_if::ExponentialBackoffConfig;
 /// # use tokio::time::Duration;
 ///
-/// const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-///     max_retries: 25,
-///     t_wait: Duration::from_secs(1),
-///     backoff: 2.0,
-///     t_wait_max: Some(Duration::from_secs(600)),
-///     backoff_max: None,
-/// };
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1))
+///         .max_retries(25)
+///         .t_wait_max(Duration::from_secs(600));
+/// ```
+///
+/// # Example: Backoff With Jitter
+/// This backoff configuration waits a random time up to the usual exponential delay, so that many
+/// callers failing together spread their retries out rather than retrying in lockstep.
+/// ```
+/// # use crate::retry_if::{ExponentialBackoffConfig, Jitter};
+/// # use tokio::time::Duration;
+///
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1))
+///         .max_retries(5)
+///         .jitter(Jitter::Full);
 /// ```
 ///
 /// # Example: Limited Backoff
@@ -74,36 +79,259 @@ use std::time::Duration;
 /// # use crate::retry_if::ExponentialBackoffConfig;
 /// # use tokio::time::Duration;
 ///
-/// const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-///     max_retries: 15,
-///     t_wait: Duration::from_secs(1),
-///     backoff: 2.0,
-///     t_wait_max: None,
-///     backoff_max: Some(Duration::from_secs(30)),
-/// };
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1))
+///         .max_retries(15)
+///         .backoff_max(Duration::from_secs(30));
 /// ```
-
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+///
+/// # Validation
+/// Nothing stops a configuration from being constructed with a negative `max_retries`, a `backoff`
+/// below 1.0, or a `backoff_max` shorter than `t_wait`. [validate] reports these as a
+/// [ConfigError], and deserializing a configuration validates it the same way. Waits computed from
+/// an invalid configuration saturate rather than panic, e.g. a `backoff` that overflows the wait
+/// is treated as waiting for as long as possible, up to `backoff_max`.
+///
+/// [validate]: ExponentialBackoffConfig::validate
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawExponentialBackoffConfig")
+)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ExponentialBackoffConfig {
     /// maximum number of retry attempts to make
     pub max_retries: i32,
     /// initial duration to wait
+    #[cfg_attr(feature = "serde", serde(with = "crate::durations"))]
     pub t_wait: Duration,
     /// backoff exponent, e.g. `2.0` for a classic exponential backoff
     pub backoff: f64,
     /// maximum time to attempt retries before returning the last result
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
     pub t_wait_max: Option<Duration>,
     /// maximum time to wait for any single retry, i.e. backoff exponentially up to this duration,
     /// then wait in constant time of `backoff_max`
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
     pub backoff_max: Option<Duration>,
+    /// randomization applied to each wait, or `None` to wait exactly as configured
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter: Option<Jitter>,
+    /// seed for the jitter's random numbers, to wait the same delays on every call, e.g. in tests
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter_seed: Option<u64>,
+}
+
+impl ExponentialBackoffConfig {
+    /// A configuration waiting `t_wait` on the first failure, with the defaults for everything else:
+    /// 3 retries, a `backoff` of `2.0`, no `t_wait_max` or `backoff_max`, and no jitter.
+    ///
+    /// Each default can be changed by the builder method of the same name, all of which are
+    /// `const`, so a configuration can still be built in a `const` item for `#[retry(...)]`.
+    /// ```
+    /// # use retry_if::ExponentialBackoffConfig;
+    /// # use std::time::Duration;
+    /// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ///     ExponentialBackoffConfig::new(Duration::from_secs(1))
+    ///         .max_retries(5)
+    ///         .backoff_max(Duration::from_secs(30));
+    /// ```
+    pub const fn new(t_wait: Duration) -> Self {
+        ExponentialBackoffConfig {
+            max_retries: 3,
+            t_wait,
+            backoff: 2.0,
+            t_wait_max: None,
+            backoff_max: None,
+            jitter: None,
+            jitter_seed: None,
+        }
+    }
+
+    /// Set the maximum number of retry attempts to make.
+    pub const fn max_retries(mut self, max_retries: i32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the initial duration to wait.
+    pub const fn t_wait(mut self, t_wait: Duration) -> Self {
+        self.t_wait = t_wait;
+        self
+    }
+
+    /// Set the backoff exponent, e.g. `1.0` to wait `t_wait` between every attempt.
+    pub const fn backoff(mut self, backoff: f64) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the maximum time to attempt retries before returning the last result.
+    pub const fn t_wait_max(mut self, t_wait_max: Duration) -> Self {
+        self.t_wait_max = Some(t_wait_max);
+        self
+    }
+
+    /// Set the maximum time to wait for any single retry.
+    pub const fn backoff_max(mut self, backoff_max: Duration) -> Self {
+        self.backoff_max = Some(backoff_max);
+        self
+    }
+
+    /// Randomize each wait with `jitter`.
+    pub const fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    /// Seed the jitter's random numbers, to wait the same delays on every call.
+    pub const fn jitter_seed(mut self, jitter_seed: u64) -> Self {
+        self.jitter_seed = Some(jitter_seed);
+        self
+    }
+
+    /// Check the configuration makes sense, returning the first problem found with it.
+    ///
+    /// ```
+    /// # use retry_if::{ConfigError, ExponentialBackoffConfig};
+    /// # use std::time::Duration;
+    /// let config = ExponentialBackoffConfig::new(Duration::from_secs(1)).backoff(0.5);
+    ///
+    /// assert_eq!(Err(ConfigError::InvalidBackoff(0.5)), config.validate());
+    /// ```
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_retries < 0 {
+            return Err(ConfigError::NegativeMaxRetries(self.max_retries));
+        }
+        if !self.backoff.is_finite() || self.backoff < 1.0 {
+            return Err(ConfigError::InvalidBackoff(self.backoff));
+        }
+        match self.backoff_max {
+            Some(backoff_max) if backoff_max < self.t_wait => {
+                Err(ConfigError::BackoffMaxBelowTWait {
+                    t_wait: self.t_wait,
+                    backoff_max,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for ExponentialBackoffConfig {
+    /// Retry up to 3 times, waiting 1 second at first, then 2 seconds and 4 seconds.
+    fn default() -> Self {
+        ExponentialBackoffConfig::new(Duration::from_secs(1))
+    }
+}
+
+impl BackoffStrategy for ExponentialBackoffConfig {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        let retries = retries_before(attempt, self.max_retries)?;
+        let delay = saturating_mul_f64(self.t_wait, self.backoff.powi(retries as i32));
+        Some(capped(delay, self.backoff_max))
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        self.backoff_max
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.t_wait_max
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        self.jitter
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.jitter_seed
+    }
+}
+
+/// The number of retries made before `attempt`, or `None` if `max_retries` have all been made.
+pub(crate) fn retries_before(attempt: u32, max_retries: i32) -> Option<u32> {
+    let retries = attempt.saturating_sub(1);
+    (i64::from(retries) < i64::from(max_retries)).then_some(retries)
+}
+
+/// Multiply `duration` by `factor`, saturating to `Duration::MAX` on overflow, and to zero for a
+/// negative or NaN result, where `Duration::mul_f64` would panic.
+pub(crate) fn saturating_mul_f64(duration: Duration, factor: f64) -> Duration {
+    let secs = duration.as_secs_f64() * factor;
+    Duration::try_from_secs_f64(secs).unwrap_or(if secs > 0.0 {
+        Duration::MAX
+    } else {
+        Duration::ZERO
+    })
+}
+
+/// Cap `delay` to `backoff_max`, if there is one.
+pub(crate) fn capped(delay: Duration, backoff_max: Option<Duration>) -> Duration {
+    match backoff_max {
+        Some(backoff_max) => delay.min(backoff_max),
+        None => delay,
+    }
+}
+
+/// The fields of an [ExponentialBackoffConfig] as deserialized, before they're validated.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawExponentialBackoffConfig {
+    #[serde(default = "RawExponentialBackoffConfig::default_max_retries")]
+    max_retries: i32,
+    #[serde(with = "crate::durations")]
+    t_wait: Duration,
+    #[serde(default = "RawExponentialBackoffConfig::default_backoff")]
+    backoff: f64,
+    #[serde(default, with = "crate::durations::option")]
+    t_wait_max: Option<Duration>,
+    #[serde(default, with = "crate::durations::option")]
+    backoff_max: Option<Duration>,
+    #[serde(default)]
+    jitter: Option<Jitter>,
+    #[serde(default)]
+    jitter_seed: Option<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl RawExponentialBackoffConfig {
+    /// The defaults of [ExponentialBackoffConfig::new], for fields left out.
+    const DEFAULTS: ExponentialBackoffConfig = ExponentialBackoffConfig::new(Duration::ZERO);
+
+    fn default_max_retries() -> i32 {
+        Self::DEFAULTS.max_retries
+    }
+
+    fn default_backoff() -> f64 {
+        Self::DEFAULTS.backoff
+    }
 }
 
-#[cfg(test)]
+#[cfg(feature = "serde")]
+impl TryFrom<RawExponentialBackoffConfig> for ExponentialBackoffConfig {
+    type Error = ConfigError;
+
+    fn try_from(raw: RawExponentialBackoffConfig) -> Result<Self, Self::Error> {
+        let config = ExponentialBackoffConfig {
+            max_retries: raw.max_retries,
+            t_wait: raw.t_wait,
+            backoff: raw.backoff,
+            t_wait_max: raw.t_wait_max,
+            backoff_max: raw.backoff_max,
+            jitter: raw.jitter,
+            jitter_seed: raw.jitter_seed,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
 mod tests {
     use super::*;
 
-    #[cfg(feature = "serde")]
     #[test]
     fn test_simple_deserialization() {
         let raw = r#"{"max_retries": 3, "t_wait": {"secs": 5,"nanos": 0}, "backoff": 2}"#;
@@ -113,6 +341,8 @@ mod tests {
             backoff: 2.0,
             t_wait_max: None,
             backoff_max: None,
+            jitter: None,
+            jitter_seed: None,
         };
 
         let config: ExponentialBackoffConfig = serde_json::from_str(raw).unwrap();
@@ -120,7 +350,6 @@ mod tests {
         assert_eq!(expected_config, config);
     }
 
-    #[cfg(feature = "serde")]
     #[test]
     fn test_deserialization_with_optionals() {
         let raw = r#"{
@@ -136,10 +365,147 @@ mod tests {
             backoff: 2.0,
             t_wait_max: Some(Duration::from_secs(120)),
             backoff_max: Some(Duration::from_secs(15)),
+            jitter: None,
+            jitter_seed: None,
+        };
+
+        let config: ExponentialBackoffConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_deserialization_with_jitter() {
+        let raw = r#"{
+            "max_retries": 3,
+            "t_wait": {"secs": 5,"nanos": 0},
+            "backoff": 2,
+            "jitter": "decorrelated",
+            "jitter_seed": 7
+        }"#;
+        let expected_config = ExponentialBackoffConfig {
+            max_retries: 3,
+            t_wait: Duration::from_secs(5),
+            backoff: 2.0,
+            t_wait_max: None,
+            backoff_max: None,
+            jitter: Some(Jitter::Decorrelated),
+            jitter_seed: Some(7),
         };
 
         let config: ExponentialBackoffConfig = serde_json::from_str(raw).unwrap();
 
         assert_eq!(expected_config, config);
     }
+
+    #[test]
+    fn test_deserialization_is_validated() {
+        let raw = r#"{"max_retries": -1, "t_wait": {"secs": 5,"nanos": 0}, "backoff": 2}"#;
+        let error = serde_json::from_str::<ExponentialBackoffConfig>(raw).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("max_retries must not be negative, got -1"));
+
+        let raw = r#"{
+            "max_retries": 3,
+            "t_wait": {"secs": 5,"nanos": 0},
+            "backoff": 2,
+            "backoff_max": {"secs": 1,"nanos": 0}
+        }"#;
+        let error = serde_json::from_str::<ExponentialBackoffConfig>(raw).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("backoff_max (1s) must not be shorter than t_wait (5s)"));
+    }
+
+    #[test]
+    fn test_human_readable_deserialization() {
+        let raw = r#"{
+            "max_retries": 3,
+            "t_wait": "250ms",
+            "backoff": 2,
+            "t_wait_max": "2m",
+            "backoff_max": 1.5
+        }"#;
+        let expected_config = ExponentialBackoffConfig::new(Duration::from_millis(250))
+            .t_wait_max(Duration::from_secs(120))
+            .backoff_max(Duration::from_millis(1500));
+
+        let config: ExponentialBackoffConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_toml_deserialization() {
+        let raw = r#"
+            max_retries = 5
+            t_wait = "1s"
+            backoff = 1.5
+            backoff_max = 30
+            jitter = "equal"
+        "#;
+        let expected_config = ExponentialBackoffConfig::new(Duration::from_secs(1))
+            .max_retries(5)
+            .backoff(1.5)
+            .backoff_max(Duration::from_secs(30))
+            .jitter(Jitter::Equal);
+
+        let config: ExponentialBackoffConfig = toml::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        let raw = r#"{"max_retries": 3, "t_wait": "5s", "backoff": 2, "t_wait_maximum": "1m"}"#;
+        let error = serde_json::from_str::<ExponentialBackoffConfig>(raw).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("unknown field `t_wait_maximum`"));
+
+        let raw = r#"{"max_retries": 3, "t_wait": "-5s", "backoff": 2}"#;
+        assert!(serde_json::from_str::<ExponentialBackoffConfig>(raw).is_err());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let config = ExponentialBackoffConfig::new(Duration::from_millis(1500))
+            .max_retries(5)
+            .t_wait_max(Duration::from_secs(90))
+            .jitter(Jitter::Full)
+            .jitter_seed(7);
+
+        let serialized = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            r#"{"max_retries":5,"t_wait":"1s 500ms","backoff":2.0,"t_wait_max":"1m 30s","backoff_max":null,"jitter":"full","jitter_seed":7}"#,
+            serialized
+        );
+        assert_eq!(config, serde_json::from_str(&serialized).unwrap());
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert_eq!(config, toml::from_str(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let config = ExponentialBackoffConfig::new(Duration::from_millis(1500))
+            .max_retries(5)
+            .backoff_max(Duration::from_secs(30))
+            .jitter(Jitter::Equal);
+
+        // postcard isn't self-describing, so durations keep their own representation
+        let serialized = postcard::to_allocvec(&config).unwrap();
+        assert_eq!(config, postcard::from_bytes(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_defaults_for_missing_fields() {
+        let config: ExponentialBackoffConfig = toml::from_str(r#"t_wait = "1s""#).unwrap();
+
+        assert_eq!(
+            ExponentialBackoffConfig::new(Duration::from_secs(1)),
+            config
+        );
+    }
 }
@@ -0,0 +1,70 @@
+use crate::{BackoffStrategy, StopReason};
+use std::fmt;
+use std::time::Duration;
+
+/// Where a decorated function is in its retries after an attempt.
+///
+/// This is given to `retry_if` predicates that take it as a second argument, and to `on_retry`
+/// hooks.
+#[derive(Clone, Copy)]
+#[non_exhaustive]
+pub struct RetryContext<'a> {
+    /// The attempt that just finished, starting from 1 for the initial call.
+    pub attempt: u32,
+    /// How long the function will wait before the next attempt if it's retried, or `None` if the
+    /// strategy won't retry it again. This is always `Some` for `on_retry` hooks.
+    pub delay: Option<Duration>,
+    /// The time since the function was first called.
+    pub elapsed: Duration,
+    /// The strategy the function is retrying with.
+    pub config: &'a (dyn BackoffStrategy + Send + Sync),
+}
+
+impl<'a> RetryContext<'a> {
+    pub(crate) fn new(
+        attempt: u32,
+        delay: Option<Duration>,
+        elapsed: Duration,
+        config: &'a (dyn BackoffStrategy + Send + Sync),
+    ) -> Self {
+        RetryContext {
+            attempt,
+            delay,
+            elapsed,
+            config,
+        }
+    }
+}
+
+impl fmt::Debug for RetryContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryContext")
+            .field("attempt", &self.attempt)
+            .field("delay", &self.delay)
+            .field("elapsed", &self.elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How a decorated function ran out of retries, given to an `on_giveup` hook with the final result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct GiveUpContext {
+    /// The number of attempts made, including the initial call.
+    pub attempts: u32,
+    /// The time since the function was first called.
+    pub elapsed: Duration,
+    /// Whether `max_retries` or `t_wait_max` ended the retries. This is never
+    /// [StopReason::NotRetried].
+    pub reason: StopReason,
+}
+
+impl GiveUpContext {
+    pub(crate) fn new(attempts: u32, elapsed: Duration, reason: StopReason) -> Self {
+        GiveUpContext {
+            attempts,
+            elapsed,
+            reason,
+        }
+    }
+}
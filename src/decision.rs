@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant};
+
+/// What a `retry_if` predicate decided to do with the result of an attempt.
+///
+/// Predicates can return this in place of a `bool`, where `true` is [RetryDecision::Retry] and
+/// `false` is [RetryDecision::Stop]. Waits chosen with [RetryDecision::RetryAfter] or
+/// [RetryDecision::RetryAt] are still capped by `backoff_max`, and are still stopped by
+/// `max_retries` and `t_wait_max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RetryDecision {
+    /// Return the result without retrying.
+    Stop,
+    /// Retry after the delay computed from the configuration.
+    Retry,
+    /// Retry after the given delay instead of the computed one, such as from a `Retry-After`
+    /// header.
+    RetryAfter(Duration),
+    /// Retry at the given time instead of after the computed delay, such as from an
+    /// `X-RateLimit-Reset` header. Times that have already passed retry immediately.
+    ///
+    /// This is compared against the `Sleeper`'s clock, so with tokio it should come from
+    /// `tokio::time::Instant::into_std()`.
+    RetryAt(Instant),
+}
+
+impl RetryDecision {
+    /// The delay to wait before retrying, given the `computed` delay and the current time.
+    pub(crate) fn delay(self, computed: Duration, now: Instant, backoff_max: Duration) -> Duration {
+        match self {
+            RetryDecision::Stop | RetryDecision::Retry => computed,
+            RetryDecision::RetryAfter(delay) => delay.min(backoff_max),
+            RetryDecision::RetryAt(at) => at.saturating_duration_since(now).min(backoff_max),
+        }
+    }
+}
+
+impl From<bool> for RetryDecision {
+    fn from(retry: bool) -> Self {
+        if retry {
+            RetryDecision::Retry
+        } else {
+            RetryDecision::Stop
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Serde support for the durations in backoff configurations.
+//!
+//! Durations serialize as human-readable strings like `"5s"` or `"1m 30s"`, and deserialize from
+//! those, from a number of seconds like `2.5`, or from serde's own `{"secs": 5, "nanos": 0}`.
+//!
+//! Formats that aren't human-readable, such as postcard or bincode, keep [Duration]'s own
+//! representation instead, since they can't tell a string from a number without being told which
+//! to expect.
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+pub(crate) fn serialize<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&humantime::format_duration(*duration))
+    } else {
+        duration.serialize(serializer)
+    }
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Duration, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(DurationVisitor)
+    } else {
+        Duration::deserialize(deserializer)
+    }
+}
+
+/// A [Duration] serialized as above, for nesting in other types.
+struct FormattedDuration<'a>(&'a Duration);
+
+impl Serialize for FormattedDuration<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+/// A [Duration] deserialized in any of the supported formats, for nesting in other types.
+struct AnyDuration(Duration);
+
+impl<'de> Deserialize<'de> for AnyDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(AnyDuration)
+    }
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a duration like \"5s\" or \"250ms\", a number of seconds, or secs and nanos")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Duration, E> {
+        humantime::parse_duration(value).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(secs))
+    }
+
+    fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Duration, E> {
+        u64::try_from(secs)
+            .map(Duration::from_secs)
+            .map_err(|_| E::custom(format!("a duration can't be negative, got {secs}")))
+    }
+
+    fn visit_f64<E: de::Error>(self, secs: f64) -> Result<Duration, E> {
+        Duration::try_from_secs_f64(secs).map_err(|e| E::custom(format!("{e}, got {secs}")))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Duration, A::Error> {
+        Duration::deserialize(de::value::MapAccessDeserializer::new(map))
+    }
+}
+
+pub(crate) mod option {
+    use super::{AnyDuration, FormattedDuration};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&FormattedDuration(duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<AnyDuration>::deserialize(deserializer)?.map(|AnyDuration(d)| d))
+    }
+}
+
+pub(crate) mod list {
+    use super::{AnyDuration, FormattedDuration, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserializer, Serializer};
+    use std::borrow::Cow;
+    use std::fmt;
+    use std::time::Duration;
+
+    pub(crate) fn serialize<S: Serializer>(
+        durations: &[Duration],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(durations.len()))?;
+        for duration in durations.iter() {
+            seq.serialize_element(&FormattedDuration(duration))?;
+        }
+        seq.end()
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Cow<'static, [Duration]>, D::Error> {
+        deserializer.deserialize_seq(ListVisitor)
+    }
+
+    struct ListVisitor;
+
+    impl<'de> Visitor<'de> for ListVisitor {
+        type Value = Cow<'static, [Duration]>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of durations")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut durations = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(AnyDuration(duration)) = seq.next_element()? {
+                durations.push(duration);
+            }
+            Ok(Cow::Owned(durations))
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{ConfigError, ExponentialBackoffConfig, Jitter};
+use std::env::{self, VarError};
+use std::time::Duration;
+
+impl ExponentialBackoffConfig {
+    /// Read a configuration from environment variables named after `prefix`, using the
+    /// [default](ExponentialBackoffConfig::default) for any that aren't set.
+    ///
+    /// See [with_env](ExponentialBackoffConfig::with_env) for the variables read.
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        ExponentialBackoffConfig::default().with_env(prefix)
+    }
+
+    /// Override this configuration with any environment variables set for `prefix`, validating
+    /// the result.
+    ///
+    /// Each field is read from `<prefix>_<FIELD>`, e.g. `RETRY_IF_DB_MAX_RETRIES` for the prefix
+    /// `RETRY_IF_DB`. Durations are written like `"250ms"` or `"1m 30s"`, or as a number of
+    /// seconds like `2.5`, and `JITTER` is one of `full`, `equal` or `decorrelated`.
+    ///
+    /// ```
+    /// # use retry_if::ExponentialBackoffConfig;
+    /// # use std::time::Duration;
+    /// const BASE: ExponentialBackoffConfig =
+    ///     ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+    ///
+    /// // RETRY_IF_DB_T_WAIT=250ms RETRY_IF_DB_BACKOFF_MAX=30s
+    /// let config = BASE.with_env("RETRY_IF_DB")?;
+    /// # Ok::<(), retry_if::ConfigError>(())
+    /// ```
+    pub fn with_env(mut self, prefix: &str) -> Result<Self, ConfigError> {
+        let var = |field| read_var(&format!("{prefix}_{field}"));
+
+        if let Some((name, value)) = var("MAX_RETRIES")? {
+            self.max_retries = parse(name, value, |v| v.parse().ok())?;
+        }
+        if let Some((name, value)) = var("T_WAIT")? {
+            self.t_wait = parse(name, value, parse_duration)?;
+        }
+        if let Some((name, value)) = var("BACKOFF")? {
+            self.backoff = parse(name, value, |v| v.parse().ok())?;
+        }
+        if let Some((name, value)) = var("T_WAIT_MAX")? {
+            self.t_wait_max = Some(parse(name, value, parse_duration)?);
+        }
+        if let Some((name, value)) = var("BACKOFF_MAX")? {
+            self.backoff_max = Some(parse(name, value, parse_duration)?);
+        }
+        if let Some((name, value)) = var("JITTER")? {
+            self.jitter = Some(parse(name, value, parse_jitter)?);
+        }
+        if let Some((name, value)) = var("JITTER_SEED")? {
+            self.jitter_seed = Some(parse(name, value, |v| v.parse().ok())?);
+        }
+
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+/// The name and value of the variable `name`, if it's set.
+fn read_var(name: &str) -> Result<Option<(String, String)>, ConfigError> {
+    match env::var(name) {
+        Ok(value) => Ok(Some((name.to_string(), value))),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(VarError::NotUnicode(value)) => Err(ConfigError::InvalidEnvVar {
+            name: name.to_string(),
+            value: value.to_string_lossy().into_owned(),
+        }),
+    }
+}
+
+fn parse<T>(
+    name: String,
+    value: String,
+    parser: impl FnOnce(&str) -> Option<T>,
+) -> Result<T, ConfigError> {
+    parser(value.trim()).ok_or(ConfigError::InvalidEnvVar { name, value })
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    match value.parse::<f64>() {
+        Ok(secs) => Duration::try_from_secs_f64(secs).ok(),
+        Err(_) => humantime::parse_duration(value).ok(),
+    }
+}
+
+fn parse_jitter(value: &str) -> Option<Jitter> {
+    match value.to_lowercase().as_str() {
+        "full" => Some(Jitter::Full),
+        "equal" => Some(Jitter::Equal),
+        "decorrelated" => Some(Jitter::Decorrelated),
+        _ => None,
+    }
+}
@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Why a backoff configuration was rejected by `validate()`, or when deserializing it or reading it
+/// from the environment.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// `max_retries` was negative.
+    NegativeMaxRetries(i32),
+    /// `backoff` was below 1.0, which would shrink each wait, or was NaN or infinite.
+    InvalidBackoff(f64),
+    /// `backoff_max` was shorter than `t_wait`, so no wait could ever be `t_wait`.
+    BackoffMaxBelowTWait {
+        t_wait: Duration,
+        backoff_max: Duration,
+    },
+    /// An environment variable read by `from_env` or `with_env` couldn't be parsed.
+    InvalidEnvVar { name: String, value: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NegativeMaxRetries(max_retries) => {
+                write!(f, "max_retries must not be negative, got {max_retries}")
+            }
+            ConfigError::InvalidBackoff(backoff) => {
+                write!(
+                    f,
+                    "backoff must be a finite number of at least 1.0, got {backoff}"
+                )
+            }
+            ConfigError::BackoffMaxBelowTWait {
+                t_wait,
+                backoff_max,
+            } => write!(
+                f,
+                "backoff_max ({backoff_max:?}) must not be shorter than t_wait ({t_wait:?})"
+            ),
+            ConfigError::InvalidEnvVar { name, value } => {
+                write!(
+                    f,
+                    "environment variable {name} has an invalid value {value:?}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -0,0 +1,385 @@
+use crate::__private::{with_env, ReportBuilder, RetryLoop};
+use crate::sleeper::DefaultSleeper;
+use crate::{
+    BackoffStrategy, ExponentialBackoffConfig, GiveUpContext, RetryContext, RetryDecision,
+    RetryReport, Sleeper, StopReason,
+};
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::future::Future;
+
+/// Retry the async operation `op` according to `config`, for as long as `retry_if` asks to.
+///
+/// This runs the same retry loop as `#[retry(config, retry_if)]` on an async function, for when
+/// the operation is a closure or isn't worth a function of its own. The attempts, delays, hooks
+/// and tracing are the same as the attribute's, with the call named `"retry"`; use
+/// [RetryExt::retrying] for the other settings.
+///
+/// It sleeps with [DefaultSleeper] on whichever enabled runtime out of tokio, async-std and smol
+/// it's called from, so it's only available with one of those features.
+///
+/// # Example
+/// ```
+/// # use retry_if::ExponentialBackoffConfig;
+/// # use std::time::Duration;
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+///
+/// let mut calls = 0;
+/// let result = retry_if::retry(BACKOFF_CONFIG, |r: &Result<i32, ()>| r.is_err(), async || {
+///     calls += 1;
+///     if calls < 3 { Err(()) } else { Ok(calls) }
+/// })
+/// .await;
+///
+/// assert_eq!(result, Ok(3));
+/// # }
+/// ```
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+pub async fn retry<S, T, D>(config: S, retry_if: impl Fn(&T) -> D, op: impl AsyncFnMut() -> T) -> T
+where
+    S: BackoffStrategy + Send + Sync,
+    D: Into<RetryDecision>,
+{
+    op.retrying(config, retry_if).run().await
+}
+
+/// Retrying for async closures and functions taking no arguments, as an alternative to
+/// `#[retry(...)]`.
+///
+/// Each method returns a [Retry], which runs the same retry loop as the attribute once
+/// [run](Retry::run) is awaited, and can be given the attribute's other settings first.
+///
+/// # Example
+/// ```
+/// # use retry_if::{ExponentialBackoffConfig, RetryExt, StopReason};
+/// # use std::time::Duration;
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// const BACKOFF_CONFIG: ExponentialBackoffConfig =
+///     ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2);
+///
+/// async fn fetch() -> Result<String, String> {
+///     Err("unavailable".to_string())
+/// }
+///
+/// let (result, report) = fetch
+///     .retrying(BACKOFF_CONFIG, Result::is_err)
+///     .name("fetch")
+///     .on_giveup(async |result: &Result<String, String>, context: &retry_if::GiveUpContext| {
+///         eprintln!("giving up after {} attempts: {result:?}", context.attempts);
+///     })
+///     .run_with_report()
+///     .await;
+///
+/// assert!(result.is_err());
+/// assert_eq!(report.attempts, 3);
+/// assert_eq!(report.reason, StopReason::RetriesExhausted);
+/// # }
+/// ```
+pub trait RetryExt<T>: AsyncFnMut() -> T + Sized {
+    /// Retry according to `config` for as long as `retry_if` asks to, given each result.
+    fn retrying<S, D>(
+        self,
+        config: S,
+        retry_if: impl Fn(&T) -> D,
+    ) -> Retry<T, Self, S, impl Fn(&T, &RetryContext) -> RetryDecision>
+    where
+        S: BackoffStrategy + Send + Sync,
+        D: Into<RetryDecision>,
+    {
+        Retry::new(self, config, move |result: &T, _: &RetryContext| {
+            retry_if(result).into()
+        })
+    }
+
+    /// Retry according to `config` for as long as `retry_if` asks to, given each result and the
+    /// [RetryContext] of its attempt.
+    fn retrying_with_context<S, D>(
+        self,
+        config: S,
+        retry_if: impl Fn(&T, &RetryContext) -> D,
+    ) -> Retry<T, Self, S, impl Fn(&T, &RetryContext) -> RetryDecision>
+    where
+        S: BackoffStrategy + Send + Sync,
+        D: Into<RetryDecision>,
+    {
+        Retry::new(self, config, move |result: &T, context: &RetryContext| {
+            retry_if(result, context).into()
+        })
+    }
+}
+
+impl<T, F: AsyncFnMut() -> T> RetryExt<T> for F {}
+
+/// An operation to retry, created with [RetryExt], which starts retrying once
+/// [run](Retry::run) or [run_with_report](Retry::run_with_report) is awaited.
+///
+/// Until then, the settings `#[retry(...)]` takes as options can be given with the methods of the
+/// same names.
+#[must_use = "the operation isn't run until `run` is awaited"]
+pub struct Retry<T, F, S, P, Sl = DefaultSleeper, R = NoHook, G = NoHook> {
+    op: F,
+    config: S,
+    retry_if: P,
+    sleeper: Sl,
+    on_retry: R,
+    on_giveup: G,
+    settings: Settings<T>,
+}
+
+/// The settings of a [Retry] that don't change its type.
+struct Settings<T> {
+    name: Cow<'static, str>,
+    #[cfg(feature = "tracing")]
+    level: tracing::Level,
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::Span>,
+    failures: Option<fn(&T) -> String>,
+}
+
+impl<T, F, S, P> Retry<T, F, S, P> {
+    fn new(op: F, config: S, retry_if: P) -> Self {
+        Retry {
+            op,
+            config,
+            retry_if,
+            sleeper: DefaultSleeper,
+            on_retry: NoHook,
+            on_giveup: NoHook,
+            settings: Settings {
+                name: Cow::Borrowed("retry"),
+                #[cfg(feature = "tracing")]
+                level: tracing::Level::INFO,
+                #[cfg(feature = "tracing")]
+                span: None,
+                failures: None,
+            },
+        }
+    }
+}
+
+impl<T, F, S, P, Sl, R, G> Retry<T, F, S, P, Sl, R, G> {
+    /// Name the call in its tracing spans and events, which is `"retry"` otherwise.
+    pub fn name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.settings.name = name.into();
+        self
+    }
+
+    /// Emit the spans and events at `level`, which is `INFO` otherwise.
+    #[cfg(feature = "tracing")]
+    pub fn level(mut self, level: tracing::Level) -> Self {
+        self.settings.level = level;
+        self
+    }
+
+    /// Run within `span` instead of a new `retry` span, e.g. to record more fields on it.
+    #[cfg(feature = "tracing")]
+    pub fn span(mut self, span: tracing::Span) -> Self {
+        self.settings.span = Some(span);
+        self
+    }
+
+    /// Include the `Debug` rendering of each retried result in the report returned by
+    /// [run_with_report](Retry::run_with_report).
+    pub fn report_failures(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.settings.failures = Some(|result| format!("{result:?}"));
+        self
+    }
+
+    /// Sleep between attempts with `sleeper`, instead of with [DefaultSleeper].
+    pub fn sleeper<Sl2: Sleeper>(self, sleeper: Sl2) -> Retry<T, F, S, P, Sl2, R, G> {
+        Retry {
+            op: self.op,
+            config: self.config,
+            retry_if: self.retry_if,
+            sleeper,
+            on_retry: self.on_retry,
+            on_giveup: self.on_giveup,
+            settings: self.settings,
+        }
+    }
+
+    /// Run `hook` before sleeping for each retry, with the result being retried and the
+    /// [RetryContext] of its attempt, holding the delay that's about to be waited.
+    pub fn on_retry<R2>(self, hook: R2) -> Retry<T, F, S, P, Sl, R2, G>
+    where
+        R2: AsyncFnMut(&T, &RetryContext),
+    {
+        Retry {
+            op: self.op,
+            config: self.config,
+            retry_if: self.retry_if,
+            sleeper: self.sleeper,
+            on_retry: hook,
+            on_giveup: self.on_giveup,
+            settings: self.settings,
+        }
+    }
+
+    /// Run `hook` when retries run out or the deadline is reached while the result still asks to
+    /// be retried, with that result and the [GiveUpContext] saying why retrying stopped.
+    pub fn on_giveup<G2>(self, hook: G2) -> Retry<T, F, S, P, Sl, R, G2>
+    where
+        G2: AsyncFnMut(&T, &GiveUpContext),
+    {
+        Retry {
+            op: self.op,
+            config: self.config,
+            retry_if: self.retry_if,
+            sleeper: self.sleeper,
+            on_retry: self.on_retry,
+            on_giveup: hook,
+            settings: self.settings,
+        }
+    }
+}
+
+impl<T, F, P, Sl, R, G> Retry<T, F, ExponentialBackoffConfig, P, Sl, R, G> {
+    /// Override the configuration from `{prefix}_*` environment variables, as
+    /// [ExponentialBackoffConfig::with_env] does, keeping it as it is if they can't be read.
+    pub fn env(mut self, prefix: &str) -> Self {
+        self.config = with_env(self.config, prefix);
+        self
+    }
+}
+
+impl<T, F, S, P, Sl, R, G> Retry<T, F, S, P, Sl, R, G>
+where
+    F: AsyncFnMut() -> T,
+    S: BackoffStrategy + Send + Sync,
+    P: Fn(&T, &RetryContext) -> RetryDecision,
+    Sl: Sleeper,
+    R: for<'a> Hook<T, RetryContext<'a>>,
+    G: Hook<T, GiveUpContext>,
+{
+    /// Run the operation until it succeeds or retrying stops, returning its last result.
+    pub async fn run(self) -> T {
+        self.run_loop(None).await.0
+    }
+
+    /// Run the operation like [run](Retry::run), returning a [RetryReport] of its retries along
+    /// with its last result.
+    pub async fn run_with_report(self) -> (T, RetryReport) {
+        let report = ReportBuilder::new(self.settings.failures.is_some());
+        let (result, report) = self.run_loop(Some(report)).await;
+        (
+            result,
+            report.expect("a report is always finished when one is started"),
+        )
+    }
+
+    /// The retry loop, keeping track of it in `report` if one is given.
+    async fn run_loop(self, mut report: Option<ReportBuilder>) -> (T, Option<RetryReport>) {
+        let Retry {
+            mut op,
+            config,
+            retry_if,
+            sleeper,
+            mut on_retry,
+            mut on_giveup,
+            settings,
+        } = self;
+        let Settings {
+            name: _name,
+            #[cfg(feature = "tracing")]
+            level,
+            #[cfg(feature = "tracing")]
+            span,
+            failures,
+        } = settings;
+
+        #[cfg(feature = "tracing")]
+        let trace = crate::__private::Trace::new(&_name, level);
+        #[cfg(feature = "tracing")]
+        let call_span = span.unwrap_or_else(|| trace.call_span());
+
+        let body = async move {
+            // read once, so a configuration that changes mid-call can't mix old and new values
+            let mut state = RetryLoop::new(config, sleeper.now());
+
+            loop {
+                #[cfg(feature = "tracing")]
+                let attempt_span = trace.attempt_span(&state, sleeper.now());
+                let attempt_start = sleeper.now();
+
+                #[cfg(feature = "tracing")]
+                let result = tracing::Instrument::instrument(op(), attempt_span.clone()).await;
+                #[cfg(not(feature = "tracing"))]
+                let result = op().await;
+
+                if let Some(report) = &mut report {
+                    report.attempt(sleeper.now() - attempt_start);
+                }
+
+                // Return result if retry isn't required, or if we ran out of attempts or time
+                let retry_wait = match state.next_wait(&result, sleeper.now(), &retry_if) {
+                    Ok(retry_wait) => retry_wait,
+                    Err(reason) => {
+                        #[cfg(feature = "tracing")]
+                        trace.stopped(&state, sleeper.now(), reason);
+                        if reason != StopReason::NotRetried {
+                            on_giveup
+                                .call(&result, state.give_up_context(sleeper.now(), reason))
+                                .await;
+                        }
+                        let report = report
+                            .map(|report| report.finish(reason, state.elapsed(sleeper.now())));
+                        return (result, report);
+                    }
+                };
+
+                #[cfg(feature = "tracing")]
+                trace.sleeping(&attempt_span, &state, retry_wait);
+                if let Some(report) = &mut report {
+                    report.retry(retry_wait);
+                    if let Some(render) = failures {
+                        report.rendered_failure(render(&result));
+                    }
+                }
+                on_retry
+                    .call(&result, state.retry_context(retry_wait))
+                    .await;
+                state.retrying();
+                sleeper.sleep(retry_wait).await;
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        let output = tracing::Instrument::instrument(body, call_span).await;
+        #[cfg(not(feature = "tracing"))]
+        let output = body.await;
+        output
+    }
+}
+
+/// An `on_retry` or `on_giveup` hook given to a [Retry], which is any async closure or function
+/// taking the result and the context of the attempt.
+pub trait Hook<T, C> {
+    /// Run the hook for `result`, with the `context` of its attempt.
+    ///
+    /// The context is given by value, so the returned future owns it rather than borrowing a
+    /// temporary of the retry loop across an `.await`.
+    fn call(&mut self, result: &T, context: C) -> impl Future<Output = ()>;
+}
+
+impl<T, C, F: AsyncFnMut(&T, &C)> Hook<T, C> for F {
+    async fn call(&mut self, result: &T, context: C) {
+        self(result, &context).await
+    }
+}
+
+/// The [Hook] of a [Retry] that wasn't given one, which does nothing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoHook;
+
+impl<T, C> Hook<T, C> for NoHook {
+    fn call(&mut self, _result: &T, _context: C) -> impl Future<Output = ()> {
+        std::future::ready(())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::configuration::saturating_mul_f64;
+use crate::BackoffStrategy;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Randomization applied to each delay, so that many callers failing at once don't retry in
+/// lockstep.
+///
+/// The modes follow the AWS Architecture Blog's "Exponential Backoff And Jitter", where `delay` is
+/// the delay the strategy would otherwise wait:
+/// - [Jitter::Full] waits a random time between zero and `delay`
+/// - [Jitter::Equal] waits half of `delay`, plus a random time up to the other half
+/// - [Jitter::Decorrelated] waits a random time between the first delay and three times the
+///   previous wait, which replaces the strategy's own growth
+///
+/// All of these are capped by the strategy's `max_delay`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Jitter {
+    Full,
+    Equal,
+    Decorrelated,
+}
+
+/// The jitter applied to the delays of a single call, which carries the previous wait for
+/// [Jitter::Decorrelated].
+///
+/// This is only reachable through `__private`, for the code generated by `#[retry(...)]`.
+#[derive(Debug)]
+pub struct JitterState {
+    rng: SplitMix64,
+    first: Option<Duration>,
+    previous: Option<Duration>,
+}
+
+impl JitterState {
+    /// Start jittering a call's delays, seeded from the strategy if it gives a seed.
+    pub fn new<S: BackoffStrategy + ?Sized>(strategy: &S) -> Self {
+        let seed = strategy
+            .rng_seed()
+            .unwrap_or_else(|| RandomState::new().hash_one(0u64));
+
+        JitterState {
+            rng: SplitMix64(seed),
+            first: None,
+            previous: None,
+        }
+    }
+
+    /// Apply the strategy's jitter to the `delay` it gave for an attempt.
+    pub fn apply<S: BackoffStrategy + ?Sized>(
+        &mut self,
+        strategy: &S,
+        delay: Option<Duration>,
+    ) -> Option<Duration> {
+        let (Some(jitter), Some(delay)) = (strategy.jitter_mode(), delay) else {
+            return delay;
+        };
+        let max_delay = strategy.max_delay().unwrap_or(Duration::MAX);
+
+        let jittered = match jitter {
+            Jitter::Full => saturating_mul_f64(delay, self.rng.next_f64()),
+            Jitter::Equal => {
+                let half = delay / 2;
+                half.saturating_add(saturating_mul_f64(half, self.rng.next_f64()))
+            }
+            Jitter::Decorrelated => {
+                let first = *self.first.get_or_insert(delay);
+                let upper = self.previous.unwrap_or(first).saturating_mul(3);
+                first.saturating_add(saturating_mul_f64(
+                    upper.saturating_sub(first),
+                    self.rng.next_f64(),
+                ))
+            }
+        }
+        .min(max_delay);
+
+        self.previous = Some(jittered);
+        Some(jittered)
+    }
+}
+
+/// A small, fast generator, which is plenty for spreading out retries.
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[0, 1)`, from the top 53 bits of the next output.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
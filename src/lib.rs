@@ -1,4 +1,45 @@
+mod backoff;
 mod configuration;
+mod context;
+mod decision;
+#[cfg(feature = "serde")]
+mod durations;
+mod env;
+mod error;
+mod function;
+mod jitter;
+mod outcome;
+mod report;
+mod retry_loop;
+mod schedule;
+mod shared;
+mod sleeper;
+mod strategies;
+#[cfg(feature = "tracing")]
+mod trace;
 
+#[doc(hidden)]
+pub mod __private;
+
+pub use backoff::BackoffStrategy;
 pub use configuration::ExponentialBackoffConfig;
+pub use context::{GiveUpContext, RetryContext};
+pub use decision::RetryDecision;
+pub use error::ConfigError;
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+pub use function::retry;
+pub use function::{Hook, NoHook, Retry, RetryExt};
+pub use jitter::Jitter;
+pub use outcome::StopReason;
+pub use report::RetryReport;
 pub use retry_if_macro::retry;
+pub use schedule::Delays;
+pub use shared::SharedBackoff;
+#[cfg(feature = "async-std")]
+pub use sleeper::AsyncStdSleeper;
+#[cfg(feature = "smol")]
+pub use sleeper::SmolSleeper;
+#[cfg(feature = "tokio")]
+pub use sleeper::TokioSleeper;
+pub use sleeper::{DefaultSleeper, Sleeper};
+pub use strategies::{FibonacciBackoffConfig, FixedScheduleConfig, LinearBackoffConfig};
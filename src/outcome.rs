@@ -0,0 +1,27 @@
+use std::fmt;
+
+/// Why a decorated function stopped retrying and returned its last result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StopReason {
+    /// The predicate declined to retry the result.
+    ///
+    /// This is the case for successes, and for failures the predicate doesn't consider worth
+    /// retrying, such as a "not found" error, so it doesn't say whether the call succeeded.
+    NotRetried,
+    /// The predicate would retry the result, but `max_retries` retries were already made.
+    RetriesExhausted,
+    /// The predicate would retry the result, but waiting again would exceed `t_wait_max`.
+    DeadlineExceeded,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            StopReason::NotRetried => "not retried",
+            StopReason::RetriesExhausted => "max_retries exhausted",
+            StopReason::DeadlineExceeded => "t_wait_max would be exceeded",
+        };
+        f.write_str(reason)
+    }
+}
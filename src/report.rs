@@ -0,0 +1,36 @@
+use crate::StopReason;
+use std::time::Duration;
+
+/// What happened while retrying a call, returned by the `<fn>_with_report` companion that
+/// `#[retry(..., report)]` generates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct RetryReport {
+    /// The number of attempts made, including the initial call.
+    pub attempts: u32,
+    /// The delay waited after each retried attempt, one fewer than the number of attempts.
+    pub delays: Vec<Duration>,
+    /// How long each attempt took to run, not counting the delays between them.
+    pub attempt_durations: Vec<Duration>,
+    /// The time from the first attempt starting to the last one finishing.
+    pub elapsed: Duration,
+    /// Why the retries stopped.
+    pub reason: StopReason,
+    /// The `Debug` rendering of each retried result, if requested with `report_failures`.
+    ///
+    /// The final result is returned alongside the report, so it isn't included here.
+    pub failures: Option<Vec<String>>,
+}
+
+impl RetryReport {
+    pub(crate) fn new(record_failures: bool) -> Self {
+        RetryReport {
+            attempts: 0,
+            delays: Vec::new(),
+            attempt_durations: Vec::new(),
+            elapsed: Duration::ZERO,
+            reason: StopReason::NotRetried,
+            failures: record_failures.then(Vec::new),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::__private::next_wait;
+use crate::jitter::JitterState;
+use crate::{BackoffStrategy, GiveUpContext, RetryContext, RetryDecision, StopReason};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The state of a call's retries, which decides after each attempt whether to retry and how long
+/// to wait first.
+///
+/// This is the retry loop shared by the code `#[retry(...)]` generates and by
+/// [retry](crate::retry()), which only run the attempts, hooks and sleeps around it. It's only
+/// reachable through `__private`.
+pub struct RetryLoop<S> {
+    strategy: S,
+    /// The strategy's [snapshot](BackoffStrategy::snapshot) for the latest attempt, if it has one.
+    snapshot: Option<Arc<dyn BackoffStrategy + Send + Sync>>,
+    jitter: JitterState,
+    start: Instant,
+    retries: u32,
+    since_start: Duration,
+}
+
+impl<S: BackoffStrategy + Send + Sync> RetryLoop<S> {
+    /// Start retrying with `strategy`, from the first attempt starting at `start`.
+    #[inline]
+    pub fn new(strategy: S, start: Instant) -> Self {
+        RetryLoop {
+            jitter: JitterState::new(&strategy),
+            strategy,
+            snapshot: None,
+            start,
+            retries: 0,
+            since_start: Duration::ZERO,
+        }
+    }
+
+    /// The current attempt, starting from 1 for the initial call.
+    #[inline]
+    pub fn attempt(&self) -> u32 {
+        self.retries.saturating_add(1)
+    }
+
+    /// The time elapsed from the start of the first attempt until `now`.
+    #[inline]
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.start)
+    }
+
+    /// The time elapsed when the latest attempt finished.
+    #[inline]
+    pub fn since_start(&self) -> Duration {
+        self.since_start
+    }
+
+    /// Decide what to do after the current attempt finished at `now` with `result`, returning the
+    /// time to wait before retrying, or why to stop retrying instead.
+    ///
+    /// `retry_if` is given the result, and a [RetryContext] with the delay the strategy would wait.
+    #[inline]
+    pub fn next_wait<T>(
+        &mut self,
+        result: &T,
+        now: Instant,
+        retry_if: impl FnOnce(&T, &RetryContext) -> RetryDecision,
+    ) -> Result<Duration, StopReason> {
+        let attempt = self.attempt();
+        self.since_start = self.elapsed(now);
+
+        // everything below reads the same snapshot, so a strategy replaced meanwhile can't mix in
+        self.snapshot = self.strategy.snapshot();
+        let strategy: &(dyn BackoffStrategy + Send + Sync) = match &self.snapshot {
+            Some(snapshot) => &**snapshot,
+            None => &self.strategy,
+        };
+
+        let delay = self
+            .jitter
+            .apply(strategy, strategy.delay(attempt, self.since_start));
+        let context = RetryContext::new(attempt, delay, self.since_start, strategy);
+        let decision = retry_if(result, &context);
+
+        next_wait(strategy, decision, delay, now, self.since_start)
+    }
+
+    /// The strategy the latest decision was made with.
+    fn strategy(&self) -> &(dyn BackoffStrategy + Send + Sync) {
+        match &self.snapshot {
+            Some(snapshot) => &**snapshot,
+            None => &self.strategy,
+        }
+    }
+
+    /// Describe the attempt that's being retried after waiting `wait`, for `on_retry` hooks.
+    #[inline]
+    pub fn retry_context(&self, wait: Duration) -> RetryContext<'_> {
+        RetryContext::new(
+            self.attempt(),
+            Some(wait),
+            self.since_start,
+            self.strategy(),
+        )
+    }
+
+    /// Describe why retrying stopped for `reason` at `now`, for `on_giveup` hooks.
+    #[inline]
+    pub fn give_up_context(&self, now: Instant, reason: StopReason) -> GiveUpContext {
+        GiveUpContext::new(self.attempt(), self.elapsed(now), reason)
+    }
+
+    /// Move on to the next attempt, once the current one is going to be retried.
+    #[inline]
+    pub fn retrying(&mut self) {
+        self.retries = self.retries.saturating_add(1);
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for RetryLoop<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryLoop")
+            .field("strategy", &self.strategy)
+            .field("jitter", &self.jitter)
+            .field("start", &self.start)
+            .field("retries", &self.retries)
+            .field("since_start", &self.since_start)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::__private::{next_wait, JitterState};
+use crate::{BackoffStrategy, ExponentialBackoffConfig, RetryDecision};
+use std::iter::FusedIterator;
+use std::time::{Duration, Instant};
+
+/// The waits a call decorated with an [ExponentialBackoffConfig] would sleep for if every attempt
+/// failed instantly, returned by [ExponentialBackoffConfig::delays] and
+/// [ExponentialBackoffConfig::schedule_within].
+///
+/// Each wait is jittered as it would be in a call, so a configuration with jitter but no
+/// `jitter_seed` yields different waits each time it's iterated.
+#[derive(Debug)]
+pub struct Delays {
+    config: ExponentialBackoffConfig,
+    jitter: JitterState,
+    attempt: u32,
+    elapsed: Duration,
+    done: bool,
+}
+
+impl Delays {
+    fn new(config: ExponentialBackoffConfig) -> Self {
+        Delays {
+            jitter: JitterState::new(&config),
+            config,
+            attempt: 0,
+            elapsed: Duration::ZERO,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if self.done {
+            return None;
+        }
+
+        // the same steps as the loop `#[retry(...)]` generates, for a result that's always retried
+        let config = &self.config;
+        let delay = self.jitter.apply(
+            config,
+            BackoffStrategy::delay(config, self.attempt + 1, self.elapsed),
+        );
+        match next_wait(
+            config,
+            RetryDecision::Retry,
+            delay,
+            Instant::now(),
+            self.elapsed,
+        ) {
+            Ok(wait) => {
+                self.attempt += 1;
+                self.elapsed = self.elapsed.saturating_add(wait);
+                Some(wait)
+            }
+            Err(_) => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl FusedIterator for Delays {}
+
+impl ExponentialBackoffConfig {
+    /// The waits between attempts of a call that keeps failing, honoring `max_retries`,
+    /// `backoff_max`, `t_wait_max` and jitter as `#[retry(...)]` does.
+    ///
+    /// `t_wait_max` is applied as if the attempts themselves took no time, so a call whose
+    /// attempts take a while may stop sooner.
+    ///
+    /// ```
+    /// # use retry_if::ExponentialBackoffConfig;
+    /// # use std::time::Duration;
+    /// let config = ExponentialBackoffConfig::new(Duration::from_secs(1))
+    ///     .max_retries(5)
+    ///     .backoff_max(Duration::from_secs(10));
+    ///
+    /// let delays: Vec<u64> = config.delays().map(|d| d.as_secs()).collect();
+    /// assert_eq!(vec![1, 2, 4, 8, 10], delays);
+    /// assert_eq!(Duration::from_secs(25), config.delays().sum());
+    /// ```
+    pub fn delays(&self) -> Delays {
+        Delays::new(*self)
+    }
+
+    /// The waits between attempts of a call that keeps failing, as with
+    /// [delays](ExponentialBackoffConfig::delays), but stopping where `t_wait_max` would instead
+    /// of the configured one.
+    ///
+    /// ```
+    /// # use retry_if::ExponentialBackoffConfig;
+    /// # use std::time::Duration;
+    /// let config = ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(10);
+    ///
+    /// // 1s + 2s + 4s = 7s, and waiting another 8s would take longer than 10s
+    /// let delays: Vec<u64> = config
+    ///     .schedule_within(Duration::from_secs(10))
+    ///     .map(|d| d.as_secs())
+    ///     .collect();
+    /// assert_eq!(vec![1, 2, 4], delays);
+    /// ```
+    pub fn schedule_within(&self, t_wait_max: Duration) -> Delays {
+        Delays::new(self.t_wait_max(t_wait_max))
+    }
+}
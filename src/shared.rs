@@ -0,0 +1,116 @@
+use crate::{BackoffStrategy, ExponentialBackoffConfig, Jitter};
+use arc_swap::ArcSwap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A handle to a backoff strategy that can be replaced while it's in use.
+///
+/// Clones of a handle share the same strategy, so one clone can be given to `#[retry(...)]` while
+/// another is kept to [store](SharedBackoff::store) a new strategy at runtime, e.g. to lengthen
+/// backoffs during an incident. Calls that are already retrying pick up the new strategy from
+/// their next attempt, and each decision to retry reads a single
+/// [snapshot](BackoffStrategy::snapshot) of it, so it never mixes the old and new strategies.
+///
+/// Reading the current strategy doesn't lock, so retry loops never wait on an update.
+///
+/// # Example: Shared Policy
+/// ```
+/// # use retry_if::{retry, ExponentialBackoffConfig, SharedBackoff};
+/// # use std::sync::LazyLock;
+/// # use std::time::Duration;
+/// static BACKOFF: LazyLock<SharedBackoff> = LazyLock::new(|| {
+///     SharedBackoff::new(ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5))
+/// });
+///
+/// #[retry(&*BACKOFF, |r| r.is_err())]
+/// async fn fetch(id: u64) -> Result<u64, String> {
+///     Ok(id)
+/// }
+///
+/// // elsewhere, e.g. in an admin endpoint
+/// BACKOFF.update(|config| config.t_wait(Duration::from_secs(5)));
+/// ```
+pub struct SharedBackoff<S = ExponentialBackoffConfig> {
+    strategy: Arc<ArcSwap<S>>,
+}
+
+impl<S> SharedBackoff<S> {
+    /// A handle to `strategy`, which can be replaced through this handle or any clone of it.
+    pub fn new(strategy: S) -> Self {
+        SharedBackoff {
+            strategy: Arc::new(ArcSwap::from_pointee(strategy)),
+        }
+    }
+
+    /// The current strategy.
+    pub fn load(&self) -> Arc<S> {
+        self.strategy.load_full()
+    }
+
+    /// Replace the strategy for every clone of this handle.
+    pub fn store(&self, strategy: S) {
+        self.strategy.store(Arc::new(strategy));
+    }
+
+    /// Replace the strategy with one derived from the current strategy.
+    ///
+    /// `update` may be called more than once if another update happens at the same time.
+    pub fn update(&self, update: impl Fn(&S) -> S) {
+        self.strategy.rcu(|strategy| update(strategy));
+    }
+}
+
+impl<S> Clone for SharedBackoff<S> {
+    fn clone(&self) -> Self {
+        SharedBackoff {
+            strategy: Arc::clone(&self.strategy),
+        }
+    }
+}
+
+impl<S: Default> Default for SharedBackoff<S> {
+    fn default() -> Self {
+        SharedBackoff::new(S::default())
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for SharedBackoff<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedBackoff")
+            .field(&self.strategy.load())
+            .finish()
+    }
+}
+
+impl<S> From<S> for SharedBackoff<S> {
+    fn from(strategy: S) -> Self {
+        SharedBackoff::new(strategy)
+    }
+}
+
+impl<S: BackoffStrategy + Send + Sync + 'static> BackoffStrategy for SharedBackoff<S> {
+    fn delay(&self, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        self.strategy.load().delay(attempt, elapsed)
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        self.strategy.load().max_delay()
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.strategy.load().max_elapsed()
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        self.strategy.load().jitter_mode()
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.strategy.load().rng_seed()
+    }
+
+    fn snapshot(&self) -> Option<Arc<dyn BackoffStrategy + Send + Sync>> {
+        Some(self.load())
+    }
+}
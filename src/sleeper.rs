@@ -0,0 +1,172 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// The clock and timer used by async functions decorated with `#[retry(...)]`.
+///
+/// This is what makes the retry loop independent of any one async runtime. Implementations are
+/// provided for tokio, async-std and smol behind the cargo features of the same names, and the
+/// macro uses [DefaultSleeper] to pick between the enabled ones unless a sleeper is given with
+/// `#[retry(..., sleeper = ...)]`.
+///
+/// `now()` measures the overall time spent retrying against `t_wait_max`, so it should follow the
+/// same clock as `sleep()`. For example, [TokioSleeper] reads tokio's clock so that retries respect
+/// `tokio::time::pause()` in tests.
+///
+/// # Example: Custom Executor
+/// ```
+/// # use retry_if::Sleeper;
+/// # use std::future::Future;
+/// # use std::time::{Duration, Instant};
+/// struct ThreadSleeper;
+///
+/// impl Sleeper for ThreadSleeper {
+///     fn now(&self) -> Instant {
+///         Instant::now()
+///     }
+///
+///     fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+///         // a real implementation would register a timer with its executor instead of blocking
+///         std::thread::sleep(duration);
+///         std::future::ready(())
+///     }
+/// }
+/// ```
+pub trait Sleeper {
+    /// The current time, as measured by this sleeper's clock.
+    fn now(&self) -> Instant;
+
+    /// Wait for `duration` to pass without blocking the executor.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()>;
+}
+
+impl<S: Sleeper> Sleeper for &S {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        (**self).sleep(duration)
+    }
+}
+
+/// A [Sleeper] backed by `tokio::time`.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioSleeper;
+
+#[cfg(feature = "tokio")]
+impl Sleeper for TokioSleeper {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// A [Sleeper] backed by `async_std::task::sleep`.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsyncStdSleeper;
+
+#[cfg(feature = "async-std")]
+impl Sleeper for AsyncStdSleeper {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        async_std::task::sleep(duration)
+    }
+}
+
+/// A [Sleeper] backed by `smol::Timer`.
+#[cfg(feature = "smol")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SmolSleeper;
+
+#[cfg(feature = "smol")]
+impl Sleeper for SmolSleeper {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        let timer = smol::Timer::after(duration);
+        async move {
+            timer.await;
+        }
+    }
+}
+
+/// The [Sleeper] used when none is given, which sleeps on whichever enabled runtime out of tokio,
+/// async-std and smol it's called from.
+///
+/// Tokio is used when called within a tokio runtime, and otherwise async-std or smol, in that
+/// order, since those can sleep without a runtime of their own. Choosing on each call keeps the
+/// runtime features additive: a crate enabling `async-std` still sleeps on async-std when another
+/// crate in the build enables `tokio`.
+///
+/// It only implements [Sleeper] when one of those features is enabled.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultSleeper;
+
+/// The runtime a [DefaultSleeper] sleeps on.
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+#[derive(Debug, Clone, Copy)]
+enum Runtime {
+    #[cfg(feature = "tokio")]
+    Tokio,
+    #[cfg(feature = "async-std")]
+    AsyncStd,
+    #[cfg(all(feature = "smol", not(feature = "async-std")))]
+    Smol,
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+impl Runtime {
+    fn current() -> Self {
+        #[cfg(feature = "async-std")]
+        let fallback = Runtime::AsyncStd;
+        #[cfg(all(feature = "smol", not(feature = "async-std")))]
+        let fallback = Runtime::Smol;
+        // without another runtime to fall back on, tokio reports that it's missing
+        #[cfg(not(any(feature = "async-std", feature = "smol")))]
+        let fallback = Runtime::Tokio;
+
+        #[cfg(feature = "tokio")]
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Runtime::Tokio;
+        }
+        fallback
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+impl Sleeper for DefaultSleeper {
+    fn now(&self) -> Instant {
+        match Runtime::current() {
+            #[cfg(feature = "tokio")]
+            Runtime::Tokio => TokioSleeper.now(),
+            #[cfg(feature = "async-std")]
+            Runtime::AsyncStd => AsyncStdSleeper.now(),
+            #[cfg(all(feature = "smol", not(feature = "async-std")))]
+            Runtime::Smol => SmolSleeper.now(),
+        }
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        let runtime = Runtime::current();
+        async move {
+            match runtime {
+                #[cfg(feature = "tokio")]
+                Runtime::Tokio => TokioSleeper.sleep(duration).await,
+                #[cfg(feature = "async-std")]
+                Runtime::AsyncStd => AsyncStdSleeper.sleep(duration).await,
+                #[cfg(all(feature = "smol", not(feature = "async-std")))]
+                Runtime::Smol => SmolSleeper.sleep(duration).await,
+            }
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use crate::configuration::{capped, retries_before};
+use crate::{BackoffStrategy, Jitter};
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// Configuration for a linear backoff, where each wait is longer than the last by `step`.
+///
+/// This will retry a failing operation up to `max_retries`, waiting for `t_wait` on the first
+/// failure and `t_wait + step * n` on the nth retry after it. As with [ExponentialBackoffConfig],
+/// the maximum single wait can be capped by `backoff_max`, and the total waiting time before
+/// failing can be set with `t_wait_max`.
+///
+/// [ExponentialBackoffConfig]: crate::ExponentialBackoffConfig
+///
+/// # Example: Linear Backoff
+/// This backoff configuration will retry up to 5 times, waiting 1 second at first, then 1.5
+/// seconds, 2 seconds, etc.
+/// ```
+/// # use crate::retry_if::LinearBackoffConfig;
+/// # use tokio::time::Duration;
+///
+/// const BACKOFF_CONFIG: LinearBackoffConfig = LinearBackoffConfig {
+///     max_retries: 5,
+///     t_wait: Duration::from_secs(1),
+///     step: Duration::from_millis(500),
+///     t_wait_max: None,
+///     backoff_max: None,
+///     jitter: None,
+///     jitter_seed: None,
+/// };
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LinearBackoffConfig {
+    /// maximum number of retry attempts to make
+    pub max_retries: i32,
+    /// initial duration to wait
+    #[cfg_attr(feature = "serde", serde(with = "crate::durations"))]
+    pub t_wait: Duration,
+    /// duration added to the wait with each retry
+    #[cfg_attr(feature = "serde", serde(with = "crate::durations"))]
+    pub step: Duration,
+    /// maximum time to attempt retries before returning the last result
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
+    pub t_wait_max: Option<Duration>,
+    /// maximum time to wait for any single retry
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
+    pub backoff_max: Option<Duration>,
+    /// randomization applied to each wait, or `None` to wait exactly as configured
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter: Option<Jitter>,
+    /// seed for the jitter's random numbers, to wait the same delays on every call, e.g. in tests
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter_seed: Option<u64>,
+}
+
+impl BackoffStrategy for LinearBackoffConfig {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        let retries = retries_before(attempt, self.max_retries)?;
+        let delay = self
+            .t_wait
+            .saturating_add(self.step.saturating_mul(retries));
+        Some(capped(delay, self.backoff_max))
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        self.backoff_max
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.t_wait_max
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        self.jitter
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.jitter_seed
+    }
+}
+
+/// Configuration for a Fibonacci backoff, where each wait is the sum of the two before it.
+///
+/// This will retry a failing operation up to `max_retries`, waiting `t_wait` times each number of
+/// the Fibonacci sequence in turn: `t_wait`, `t_wait`, `2 * t_wait`, `3 * t_wait`, `5 * t_wait`,
+/// etc. This grows more gently than doubling the wait each time. The maximum single wait can be
+/// capped by `backoff_max`, and the total waiting time before failing can be set with
+/// `t_wait_max`.
+///
+/// # Example: Fibonacci Backoff
+/// This backoff configuration will retry up to 8 times, waiting 1 second twice, then 2 seconds,
+/// 3 seconds, 5 seconds, etc, until waiting 10 seconds for each of the last two retries.
+/// ```
+/// # use crate::retry_if::FibonacciBackoffConfig;
+/// # use tokio::time::Duration;
+///
+/// const BACKOFF_CONFIG: FibonacciBackoffConfig = FibonacciBackoffConfig {
+///     max_retries: 8,
+///     t_wait: Duration::from_secs(1),
+///     t_wait_max: None,
+///     backoff_max: Some(Duration::from_secs(10)),
+///     jitter: None,
+///     jitter_seed: None,
+/// };
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FibonacciBackoffConfig {
+    /// maximum number of retry attempts to make
+    pub max_retries: i32,
+    /// initial duration to wait, which each number of the sequence is multiplied by
+    #[cfg_attr(feature = "serde", serde(with = "crate::durations"))]
+    pub t_wait: Duration,
+    /// maximum time to attempt retries before returning the last result
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
+    pub t_wait_max: Option<Duration>,
+    /// maximum time to wait for any single retry
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
+    pub backoff_max: Option<Duration>,
+    /// randomization applied to each wait, or `None` to wait exactly as configured
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter: Option<Jitter>,
+    /// seed for the jitter's random numbers, to wait the same delays on every call, e.g. in tests
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter_seed: Option<u64>,
+}
+
+impl BackoffStrategy for FibonacciBackoffConfig {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        let retries = retries_before(attempt, self.max_retries)?;
+
+        let (mut current, mut next) = (1u32, 1u32);
+        for _ in 0..retries {
+            // saturated, so every later delay is the same
+            if current == u32::MAX {
+                break;
+            }
+            (current, next) = (next, current.saturating_add(next));
+        }
+
+        Some(capped(
+            self.t_wait.saturating_mul(current),
+            self.backoff_max,
+        ))
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        self.backoff_max
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.t_wait_max
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        self.jitter
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.jitter_seed
+    }
+}
+
+/// Configuration for an explicit schedule of waits, retrying once for each.
+///
+/// This will retry a failing operation once for each of `delays`, waiting for each in turn, so
+/// there's no separate `max_retries`. The total waiting time before failing can still be set with
+/// `t_wait_max`.
+///
+/// The schedule can be borrowed from a `const` or `static` slice, or owned, as it is when it's
+/// deserialized.
+///
+/// # Example: Fixed Schedule
+/// This configuration will retry up to 4 times, waiting 100 milliseconds, then 500 milliseconds,
+/// 2 seconds and 10 seconds.
+/// ```
+/// # use crate::retry_if::FixedScheduleConfig;
+/// # use std::borrow::Cow;
+/// # use tokio::time::Duration;
+///
+/// const BACKOFF_CONFIG: FixedScheduleConfig = FixedScheduleConfig {
+///     delays: Cow::Borrowed(&[
+///         Duration::from_millis(100),
+///         Duration::from_millis(500),
+///         Duration::from_secs(2),
+///         Duration::from_secs(10),
+///     ]),
+///     t_wait_max: None,
+///     jitter: None,
+///     jitter_seed: None,
+/// };
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedScheduleConfig {
+    /// the wait before each retry, in order
+    #[cfg_attr(feature = "serde", serde(with = "crate::durations::list"))]
+    pub delays: Cow<'static, [Duration]>,
+    /// maximum time to attempt retries before returning the last result
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::durations::option"))]
+    pub t_wait_max: Option<Duration>,
+    /// randomization applied to each wait, or `None` to wait exactly as configured
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter: Option<Jitter>,
+    /// seed for the jitter's random numbers, to wait the same delays on every call, e.g. in tests
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub jitter_seed: Option<u64>,
+}
+
+impl BackoffStrategy for FixedScheduleConfig {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        let retries = attempt.saturating_sub(1);
+        self.delays.get(retries as usize).copied()
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.t_wait_max
+    }
+
+    fn jitter_mode(&self) -> Option<Jitter> {
+        self.jitter
+    }
+
+    fn rng_seed(&self) -> Option<u64> {
+        self.jitter_seed
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_deserialization() {
+        let raw = r#"{
+            "max_retries": 3,
+            "t_wait": {"secs": 1,"nanos": 0},
+            "step": {"secs": 0,"nanos": 500000000},
+            "jitter": "full"
+        }"#;
+        let expected_config = LinearBackoffConfig {
+            max_retries: 3,
+            t_wait: Duration::from_secs(1),
+            step: Duration::from_millis(500),
+            t_wait_max: None,
+            backoff_max: None,
+            jitter: Some(Jitter::Full),
+            jitter_seed: None,
+        };
+
+        let config: LinearBackoffConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_fibonacci_deserialization() {
+        let raw = r#"{
+            "max_retries": 8,
+            "t_wait": {"secs": 1,"nanos": 0},
+            "backoff_max": {"secs": 10,"nanos": 0}
+        }"#;
+        let expected_config = FibonacciBackoffConfig {
+            max_retries: 8,
+            t_wait: Duration::from_secs(1),
+            t_wait_max: None,
+            backoff_max: Some(Duration::from_secs(10)),
+            jitter: None,
+            jitter_seed: None,
+        };
+
+        let config: FibonacciBackoffConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_fixed_schedule_deserialization() {
+        let raw = r#"{
+            "delays": [{"secs": 0,"nanos": 100000000}, {"secs": 2,"nanos": 0}],
+            "t_wait_max": {"secs": 5,"nanos": 0}
+        }"#;
+        let expected_config = FixedScheduleConfig {
+            delays: Cow::Owned(vec![Duration::from_millis(100), Duration::from_secs(2)]),
+            t_wait_max: Some(Duration::from_secs(5)),
+            jitter: None,
+            jitter_seed: None,
+        };
+
+        let config: FixedScheduleConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(expected_config, config);
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let linear = LinearBackoffConfig {
+            max_retries: 3,
+            t_wait: Duration::from_secs(1),
+            step: Duration::from_millis(500),
+            t_wait_max: None,
+            backoff_max: Some(Duration::from_secs(5)),
+            jitter: None,
+            jitter_seed: None,
+        };
+        let serialized = serde_json::to_string(&linear).unwrap();
+        assert_eq!(
+            r#"{"max_retries":3,"t_wait":"1s","step":"500ms","t_wait_max":null,"backoff_max":"5s","jitter":null,"jitter_seed":null}"#,
+            serialized
+        );
+        assert_eq!(linear, serde_json::from_str(&serialized).unwrap());
+
+        let schedule = FixedScheduleConfig {
+            delays: Cow::Owned(vec![Duration::from_millis(100), Duration::from_secs(2)]),
+            t_wait_max: None,
+            jitter: Some(Jitter::Full),
+            jitter_seed: None,
+        };
+        let serialized = toml::to_string(&schedule).unwrap();
+        assert_eq!(
+            "delays = [\"100ms\", \"2s\"]\njitter = \"full\"\n",
+            serialized
+        );
+        assert_eq!(schedule, toml::from_str(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let fibonacci = FibonacciBackoffConfig {
+            max_retries: 6,
+            t_wait: Duration::from_millis(250),
+            t_wait_max: Some(Duration::from_secs(60)),
+            backoff_max: None,
+            jitter: Some(Jitter::Full),
+            jitter_seed: Some(3),
+        };
+        let serialized = postcard::to_allocvec(&fibonacci).unwrap();
+        assert_eq!(fibonacci, postcard::from_bytes(&serialized).unwrap());
+
+        let schedule = FixedScheduleConfig {
+            delays: Cow::Owned(vec![Duration::from_millis(100), Duration::from_secs(2)]),
+            t_wait_max: None,
+            jitter: None,
+            jitter_seed: None,
+        };
+        let serialized = postcard::to_allocvec(&schedule).unwrap();
+        assert_eq!(schedule, postcard::from_bytes(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_mixed_duration_formats() {
+        let raw = r#"{"delays": ["100ms", 0.5, 2, {"secs": 10, "nanos": 0}]}"#;
+        let config: FixedScheduleConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(500),
+                Duration::from_secs(2),
+                Duration::from_secs(10),
+            ],
+            config.delays.to_vec()
+        );
+    }
+}
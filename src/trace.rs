@@ -0,0 +1,114 @@
+//! The spans and events emitted with the `tracing` feature, shared by the loops `#[retry(...)]`
+//! generates and by [Retry](crate::Retry), so both trace a call the same way.
+use crate::retry_loop::RetryLoop;
+use crate::{BackoffStrategy, StopReason};
+use std::time::{Duration, Instant};
+use tracing::{Level, Span};
+
+/// Call one of tracing's macros at `level`, which they only take as a constant, by binding each
+/// level in turn to `$constant`.
+macro_rules! at_level {
+    ($level:expr, $constant:ident => $call:expr) => {
+        match $level {
+            Level::ERROR => {
+                const $constant: Level = Level::ERROR;
+                $call
+            }
+            Level::WARN => {
+                const $constant: Level = Level::WARN;
+                $call
+            }
+            Level::INFO => {
+                const $constant: Level = Level::INFO;
+                $call
+            }
+            Level::DEBUG => {
+                const $constant: Level = Level::DEBUG;
+                $call
+            }
+            _ => {
+                const $constant: Level = Level::TRACE;
+                $call
+            }
+        }
+    };
+}
+
+/// How a call is traced: the name its spans and events carry, and the level they're emitted at.
+///
+/// Each call runs in a `retry` span, and each attempt in an `attempt` span within it, which
+/// records the attempt number (starting from 1), the time elapsed when it started, and the delay
+/// computed after it. Events are emitted before each sleep, and when the loop stops with the
+/// reason it stopped.
+#[derive(Debug, Clone, Copy)]
+pub struct Trace<'a> {
+    name: &'a str,
+    level: Level,
+}
+
+impl<'a> Trace<'a> {
+    #[inline]
+    pub fn new(name: &'a str, level: Level) -> Self {
+        Trace { name, level }
+    }
+
+    /// The span for the whole call, for calls that don't record their arguments on it.
+    pub fn call_span(&self) -> Span {
+        at_level!(self.level, LEVEL => tracing::span!(LEVEL, "retry", name = self.name))
+    }
+
+    /// The span for the attempt `state` is about to make at `now`.
+    pub fn attempt_span<S: BackoffStrategy + Send + Sync>(
+        &self,
+        state: &RetryLoop<S>,
+        now: Instant,
+    ) -> Span {
+        at_level!(self.level, LEVEL => tracing::span!(
+            LEVEL,
+            "attempt",
+            name = self.name,
+            attempt = state.attempt(),
+            elapsed = ?state.elapsed(now),
+            delay = tracing::field::Empty,
+        ))
+    }
+
+    /// Record the delay before the next attempt on the attempt's span, and log that the call is
+    /// sleeping.
+    pub fn sleeping<S: BackoffStrategy + Send + Sync>(
+        &self,
+        attempt_span: &Span,
+        state: &RetryLoop<S>,
+        wait: Duration,
+    ) {
+        attempt_span.record("delay", tracing::field::debug(wait));
+        at_level!(self.level, LEVEL => tracing::event!(
+            parent: attempt_span,
+            LEVEL,
+            name = self.name,
+            attempt = state.attempt(),
+            delay = ?wait,
+            elapsed = ?state.since_start(),
+            "Sleeping {wait:?} on attempt {}",
+            state.attempt()
+        ))
+    }
+
+    /// Log that the call stopped at `now` for `reason`.
+    pub fn stopped<S: BackoffStrategy + Send + Sync>(
+        &self,
+        state: &RetryLoop<S>,
+        now: Instant,
+        reason: StopReason,
+    ) {
+        at_level!(self.level, LEVEL => tracing::event!(
+            LEVEL,
+            name = self.name,
+            attempts = state.attempt(),
+            elapsed = ?state.elapsed(now),
+            reason = %reason,
+            "Stopped after {} attempts: {reason}",
+            state.attempt()
+        ))
+    }
+}
@@ -0,0 +1,35 @@
+//! This example tests retrying on the async-std runtime, and only runs with the `async-std` feature.
+#![cfg(feature = "async-std")]
+use retry_if::{retry, AsyncStdSleeper, ExponentialBackoffConfig};
+use std::time::{Duration, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_millis(10)).max_retries(3);
+
+#[test]
+fn test_async_std_sleeper() {
+    #[retry(BACKOFF_CONFIG, |_| true, sleeper = AsyncStdSleeper)]
+    async fn method() {}
+
+    let start = Instant::now();
+    async_std::task::block_on(method());
+    let duration = Instant::now() - start;
+
+    // max of 3 retries, waits of 10ms, 20ms, 40ms = 70ms
+    assert!(duration >= Duration::from_millis(70));
+    assert!(duration < Duration::from_millis(1000));
+}
+
+#[test]
+fn test_default_sleeper_outside_tokio() {
+    // tokio may be enabled by another crate, but there's no tokio runtime to sleep on here
+    #[retry(BACKOFF_CONFIG, |_| true)]
+    async fn method() {}
+
+    let start = Instant::now();
+    async_std::task::block_on(method());
+    let duration = Instant::now() - start;
+
+    assert!(duration >= Duration::from_millis(70));
+    assert!(duration < Duration::from_millis(1000));
+}
@@ -0,0 +1,79 @@
+//! This example tests building an `ExponentialBackoffConfig` with its `const fn` builder, both in
+//! a `const` item for `#[retry(...)]` and inline as its argument.
+use retry_if::{retry, ExponentialBackoffConfig, Jitter};
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(4)
+        .backoff(3.0)
+        .backoff_max(Duration::from_secs(5));
+
+#[retry(BACKOFF_CONFIG, |_| true)]
+async fn from_const() {}
+
+#[retry(ExponentialBackoffConfig::new(Duration::from_secs(2)).backoff(1.0), |_| true)]
+async fn inline() {}
+
+#[test]
+fn test_builder() {
+    const CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(5)
+        .t_wait(Duration::from_secs(2))
+        .backoff(1.5)
+        .t_wait_max(Duration::from_secs(60))
+        .backoff_max(Duration::from_secs(10))
+        .jitter(Jitter::Full)
+        .jitter_seed(7);
+
+    let expected = ExponentialBackoffConfig {
+        max_retries: 5,
+        t_wait: Duration::from_secs(2),
+        backoff: 1.5,
+        t_wait_max: Some(Duration::from_secs(60)),
+        backoff_max: Some(Duration::from_secs(10)),
+        jitter: Some(Jitter::Full),
+        jitter_seed: Some(7),
+    };
+
+    assert_eq!(expected, CONFIG);
+}
+
+#[test]
+fn test_default() {
+    let expected = ExponentialBackoffConfig {
+        max_retries: 3,
+        t_wait: Duration::from_secs(1),
+        backoff: 2.0,
+        t_wait_max: None,
+        backoff_max: None,
+        jitter: None,
+        jitter_seed: None,
+    };
+
+    assert_eq!(expected, ExponentialBackoffConfig::default());
+    assert_eq!(
+        expected,
+        ExponentialBackoffConfig::new(Duration::from_secs(1))
+    );
+}
+
+#[tokio::test]
+async fn test_built_configs() {
+    pause();
+
+    // waits of 1s, 3s, 5s, 5s = 14s
+    let start = Instant::now();
+    from_const().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(14));
+    assert!(duration < Duration::from_millis(14100));
+
+    // waits of 2s, 2s, 2s = 6s
+    let start = Instant::now();
+    inline().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(6));
+    assert!(duration < Duration::from_millis(6100));
+}
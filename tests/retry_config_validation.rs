@@ -0,0 +1,93 @@
+//! This example tests `ExponentialBackoffConfig::validate`, and that decorated functions still
+//! run with configurations that fail it, saturating their waits instead of panicking.
+use retry_if::{retry, ConfigError, ExponentialBackoffConfig, Jitter, StopReason};
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(3);
+
+const NEGATIVE_BACKOFF: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    backoff: -2.0,
+    ..BACKOFF_CONFIG
+};
+
+const INFINITE_BACKOFF: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    backoff: f64::INFINITY,
+    t_wait_max: Some(Duration::from_secs(10)),
+    jitter: Some(Jitter::Equal),
+    ..BACKOFF_CONFIG
+};
+
+#[retry(NEGATIVE_BACKOFF, |_| true, report)]
+async fn negative_backoff() {}
+
+#[retry(INFINITE_BACKOFF, |_| true, report)]
+async fn infinite_backoff() {}
+
+#[test]
+fn test_validate() {
+    assert_eq!(Ok(()), BACKOFF_CONFIG.validate());
+
+    let config = ExponentialBackoffConfig {
+        max_retries: -1,
+        ..BACKOFF_CONFIG
+    };
+    assert_eq!(Err(ConfigError::NegativeMaxRetries(-1)), config.validate());
+
+    for backoff in [0.5, -2.0, f64::INFINITY] {
+        let config = ExponentialBackoffConfig {
+            backoff,
+            ..BACKOFF_CONFIG
+        };
+        assert_eq!(Err(ConfigError::InvalidBackoff(backoff)), config.validate());
+    }
+
+    let config = ExponentialBackoffConfig {
+        backoff: f64::NAN,
+        ..BACKOFF_CONFIG
+    };
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::InvalidBackoff(backoff)) if backoff.is_nan()
+    ));
+
+    let config = ExponentialBackoffConfig {
+        backoff_max: Some(Duration::from_millis(500)),
+        ..BACKOFF_CONFIG
+    };
+    let error = config.validate().unwrap_err();
+    assert_eq!(
+        ConfigError::BackoffMaxBelowTWait {
+            t_wait: Duration::from_secs(1),
+            backoff_max: Duration::from_millis(500),
+        },
+        error
+    );
+    assert_eq!(
+        "backoff_max (500ms) must not be shorter than t_wait (1s)",
+        error.to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_configs_do_not_panic() {
+    pause();
+
+    // a negative wait can't be slept, so it's treated as no wait at all
+    let ((), report) = negative_backoff_with_report().await;
+    assert_eq!(4, report.attempts);
+    assert_eq!(
+        vec![
+            Duration::from_secs(1),
+            Duration::ZERO,
+            Duration::from_secs(4)
+        ],
+        report.delays
+    );
+
+    // an infinite wait saturates, so waiting it would exceed `t_wait_max`
+    let ((), report) = infinite_backoff_with_report().await;
+    assert_eq!(2, report.attempts);
+    assert_eq!(StopReason::DeadlineExceeded, report.reason);
+}
@@ -0,0 +1,102 @@
+//! This example tests `retry_if` predicates that take a `RetryContext` as well as the result, so
+//! they can decide based on the attempt, the time elapsed, the next delay or the strategy.
+//!
+//! The functions always produce a retryable result, so only the context stops them early.
+use retry_if::{retry, ExponentialBackoffConfig, RetryContext};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+
+#[derive(Debug, PartialEq)]
+struct Status(u16);
+
+// retry a 503 only on the first two attempts
+fn retry_unavailable(status: &Status, context: &RetryContext) -> bool {
+    status.0 == 503 && context.attempt <= 2
+}
+
+#[retry(BACKOFF_CONFIG, retry_unavailable)]
+async fn unavailable() -> Status {
+    Status(503)
+}
+
+#[retry(BACKOFF_CONFIG, |status, context| status.0 >= 500 && context.elapsed < Duration::from_secs(5))]
+async fn server_error() -> Status {
+    Status(500)
+}
+
+#[tokio::test]
+async fn test_function_predicate() {
+    pause();
+    let start = Instant::now();
+    unavailable().await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 2s = 3s
+    assert!(duration > Duration::from_secs(3));
+    assert!(duration < Duration::from_millis(3100));
+}
+
+#[tokio::test]
+async fn test_closure_predicate() {
+    pause();
+    let start = Instant::now();
+    server_error().await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 2s, 4s = 7s, since 3s had elapsed before the third retry
+    assert!(duration > Duration::from_secs(7));
+    assert!(duration < Duration::from_millis(7100));
+}
+
+/// The attempt, delay, elapsed time and strategy's `max_elapsed` seen by a predicate.
+type Seen = (u32, Option<Duration>, Duration, Option<Duration>);
+
+#[tokio::test]
+async fn test_context() {
+    #[retry(BACKOFF_CONFIG, |_, context: &RetryContext| {
+        let max_elapsed = context.config.max_elapsed();
+        contexts.lock().unwrap().push((context.attempt, context.delay, context.elapsed, max_elapsed));
+        true
+    })]
+    async fn method(contexts: &Mutex<Vec<Seen>>) {}
+
+    let contexts = Mutex::new(Vec::new());
+    pause();
+    method(&contexts).await;
+
+    // the predicate sees every attempt, including the last, which has no delay to follow it
+    let contexts = contexts.into_inner().unwrap();
+    let attempts: Vec<_> = contexts.iter().map(|c| c.0).collect();
+    let delays: Vec<_> = contexts.iter().map(|c| c.1.map(|d| d.as_secs())).collect();
+    let elapsed: Vec<_> = contexts.iter().map(|c| c.2.as_secs()).collect();
+
+    assert_eq!(vec![1, 2, 3, 4, 5, 6], attempts);
+    assert_eq!(
+        vec![Some(1), Some(2), Some(4), Some(8), Some(16), None],
+        delays
+    );
+    assert_eq!(vec![0, 1, 3, 7, 15, 31], elapsed);
+    assert!(contexts.iter().all(|c| c.3 == BACKOFF_CONFIG.t_wait_max));
+}
+
+#[test]
+fn test_sync_predicate() {
+    const SYNC_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        t_wait: Duration::from_millis(1),
+        ..BACKOFF_CONFIG
+    };
+
+    // retry while the next wait is short, which gives delays of 1ms, 2ms and 4ms before 8ms
+    #[retry(SYNC_CONFIG, |_, context| context.delay < Some(Duration::from_millis(8)))]
+    fn always_retried(attempts: &mut u32) {
+        *attempts += 1;
+    }
+
+    let mut attempts = 0;
+    always_retried(&mut attempts);
+    assert_eq!(4, attempts);
+}
@@ -0,0 +1,78 @@
+//! This example tests a custom `Sleeper`, which stands in for the timer of an executor other than
+//! tokio. It never actually sleeps, instead advancing its own clock and recording each wait.
+//!
+//! The expectation is that waits of 1s, 2s, 2.5s, 2.5s are requested, and then at 8s another wait
+//! of 2.5s would exceed the maximum time of 10s, so it exits early.
+use retry_if::{retry, ExponentialBackoffConfig, Sleeper};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(25)
+        .t_wait_max(Duration::from_secs(10))
+        .backoff_max(Duration::from_millis(2500));
+
+fn retry_if(_i: &i64) -> bool {
+    true
+}
+
+struct RecordingSleeper {
+    now: Mutex<Instant>,
+    sleeps: Mutex<Vec<Duration>>,
+}
+
+impl RecordingSleeper {
+    fn new() -> Self {
+        RecordingSleeper {
+            now: Mutex::new(Instant::now()),
+            sleeps: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Sleeper for RecordingSleeper {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        *self.now.lock().unwrap() += duration;
+        self.sleeps.lock().unwrap().push(duration);
+        std::future::ready(())
+    }
+}
+
+pub struct Counter {
+    pub count: i64,
+    sleeper: RecordingSleeper,
+}
+
+impl Counter {
+    #[retry(BACKOFF_CONFIG, retry_if, sleeper = &self.sleeper)]
+    async fn increase_count(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[tokio::test]
+async fn test_custom_sleeper() {
+    let mut counter = Counter {
+        count: 0,
+        sleeper: RecordingSleeper::new(),
+    };
+
+    counter.increase_count().await;
+
+    let expected = vec![
+        Duration::from_secs(1),
+        Duration::from_secs(2),
+        Duration::from_millis(2500),
+        Duration::from_millis(2500),
+    ];
+    assert_eq!(expected, *counter.sleeper.sleeps.lock().unwrap());
+    // initial attempt + 4 retries
+    assert_eq!(5, counter.count);
+}
@@ -0,0 +1,98 @@
+//! This example tests decorating functions with a custom `BackoffStrategy` in place of an
+//! `ExponentialBackoffConfig`.
+//!
+//! The strategies here wait a constant time between attempts, and the functions always retry.
+use retry_if::{retry, BackoffStrategy, ExponentialBackoffConfig, StopReason};
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+/// Waits `delay` between attempts, for up to `retries` retries.
+struct Constant {
+    delay: Duration,
+    retries: u32,
+}
+
+impl BackoffStrategy for Constant {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        (attempt <= self.retries).then_some(self.delay)
+    }
+}
+
+/// Waits 1s between attempts until `budget` has elapsed, capping any longer waits to 2s.
+struct Budgeted {
+    budget: Duration,
+}
+
+impl BackoffStrategy for Budgeted {
+    fn delay(&self, _attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        Some(Duration::from_secs(1))
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        Some(Duration::from_secs(2))
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        Some(self.budget)
+    }
+}
+
+const CONSTANT: Constant = Constant {
+    delay: Duration::from_secs(3),
+    retries: 2,
+};
+
+#[retry(CONSTANT, |_| true)]
+async fn constant() {}
+
+#[retry(Budgeted { budget: Duration::from_millis(4500) }, |_| true, report)]
+async fn budgeted() {}
+
+#[retry(&CONSTANT as &(dyn BackoffStrategy + Send + Sync), |_| true)]
+async fn dynamic() {}
+
+#[tokio::test]
+async fn test_custom_strategy() {
+    pause();
+
+    // waits of 3s, 3s = 6s
+    let start = Instant::now();
+    constant().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(6));
+    assert!(duration < Duration::from_millis(6100));
+    let start = Instant::now();
+    dynamic().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(6));
+    assert!(duration < Duration::from_millis(6100));
+}
+
+#[tokio::test]
+async fn test_strategy_limits() {
+    pause();
+
+    // waits of 1s for as long as another wouldn't exceed 4.5s
+    let ((), report) = budgeted_with_report().await;
+    assert_eq!(5, report.attempts);
+    assert_eq!(StopReason::DeadlineExceeded, report.reason);
+    assert!(report.elapsed >= Duration::from_secs(4));
+    assert!(report.elapsed < Duration::from_millis(4100));
+}
+
+#[test]
+fn test_exponential_delays() {
+    let config = ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(4)
+        .t_wait_max(Duration::from_secs(60))
+        .backoff_max(Duration::from_secs(5));
+
+    let delays: Vec<_> = (1..=5)
+        .map(|attempt| config.delay(attempt, Duration::ZERO))
+        .collect();
+    let expected = [Some(1), Some(2), Some(4), Some(5), None].map(|d| d.map(Duration::from_secs));
+
+    assert_eq!(expected.to_vec(), delays);
+    assert_eq!(Some(Duration::from_secs(5)), config.max_delay());
+    assert_eq!(Some(Duration::from_secs(60)), config.max_elapsed());
+}
@@ -0,0 +1,144 @@
+//! This example tests `retry_if` predicates returning a `RetryDecision`, which can override the
+//! computed delay, as a rate-limited API might through a `Retry-After` header.
+//!
+//! Each function retries at most twice, so the total time is the sum of two waits.
+use retry_if::{retry, ExponentialBackoffConfig, RetryDecision};
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2);
+
+#[derive(Debug)]
+enum Response {
+    Ok,
+    TooManyRequests { retry_after: Duration },
+    Unavailable,
+}
+
+fn retry_if(response: &Response) -> RetryDecision {
+    match response {
+        Response::Ok => RetryDecision::Stop,
+        Response::TooManyRequests { retry_after } => RetryDecision::RetryAfter(*retry_after),
+        Response::Unavailable => RetryDecision::Retry,
+    }
+}
+
+#[retry(BACKOFF_CONFIG, retry_if)]
+async fn rate_limited() -> Response {
+    Response::TooManyRequests {
+        retry_after: Duration::from_secs(5),
+    }
+}
+
+#[retry(BACKOFF_CONFIG, retry_if)]
+async fn unavailable() -> Response {
+    Response::Unavailable
+}
+
+#[retry(BACKOFF_CONFIG, retry_if)]
+async fn succeeds() -> Response {
+    Response::Ok
+}
+
+#[tokio::test]
+async fn test_retry_after() {
+    pause();
+
+    // waits of 5s, 5s = 10s
+    let start = Instant::now();
+    rate_limited().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(10));
+    assert!(duration < Duration::from_millis(10_100));
+
+    // computed waits of 1s, 2s = 3s
+    let start = Instant::now();
+    unavailable().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(3));
+    assert!(duration < Duration::from_millis(3100));
+
+    let start = Instant::now();
+    succeeds().await;
+    let duration = Instant::now() - start;
+    assert!(duration < Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_retry_after_respects_limits() {
+    const CAPPED_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        backoff_max: Some(Duration::from_secs(2)),
+        ..BACKOFF_CONFIG
+    };
+    const LIMITED_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        t_wait_max: Some(Duration::from_secs(8)),
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(CAPPED_CONFIG, retry_if)]
+    async fn capped() -> Response {
+        Response::TooManyRequests {
+            retry_after: Duration::from_secs(5),
+        }
+    }
+
+    #[retry(LIMITED_CONFIG, retry_if)]
+    async fn limited() -> Response {
+        Response::TooManyRequests {
+            retry_after: Duration::from_secs(5),
+        }
+    }
+
+    pause();
+
+    // waits of 5s are capped to 2s, 2s = 4s
+    let start = Instant::now();
+    capped().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(4));
+    assert!(duration < Duration::from_millis(4100));
+
+    // a wait of 5s, and another 5s would exceed 8s
+    let start = Instant::now();
+    limited().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(5));
+    assert!(duration < Duration::from_millis(5100));
+}
+
+#[tokio::test]
+async fn test_retry_at() {
+    #[retry(BACKOFF_CONFIG, |_| RetryDecision::RetryAt(reset.into_std()))]
+    async fn rate_limited_until(reset: Instant) {}
+
+    pause();
+
+    // waits until the reset in 3s, after which the reset time has passed and it retries at once
+    let reset = Instant::now() + Duration::from_secs(3);
+    let start = Instant::now();
+    rate_limited_until(reset).await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(3));
+    assert!(duration < Duration::from_millis(3100));
+
+    let reset = Instant::now() - Duration::from_secs(10);
+    let start = Instant::now();
+    rate_limited_until(reset).await;
+    let duration = Instant::now() - start;
+    assert!(duration < Duration::from_millis(100));
+}
+
+#[test]
+fn test_sync_retry_after() {
+    #[retry(BACKOFF_CONFIG, |_| RetryDecision::RetryAfter(Duration::from_millis(10)))]
+    fn always_retried() {}
+
+    let start = std::time::Instant::now();
+    always_retried();
+    let duration = std::time::Instant::now() - start;
+
+    // waits of 10ms, 10ms = 20ms, rather than 1s, 2s
+    assert!(duration >= Duration::from_millis(20));
+    assert!(duration < Duration::from_millis(500));
+}
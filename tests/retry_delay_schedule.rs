@@ -0,0 +1,68 @@
+//! This example tests `ExponentialBackoffConfig::delays` and `schedule_within`, checking they
+//! yield the same waits as a decorated function that always retries.
+use retry_if::{retry, ExponentialBackoffConfig, Jitter};
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(6)
+        .backoff_max(Duration::from_secs(10));
+
+const LIMITED_CONFIG: ExponentialBackoffConfig = BACKOFF_CONFIG.t_wait_max(Duration::from_secs(20));
+
+const JITTERED_CONFIG: ExponentialBackoffConfig =
+    BACKOFF_CONFIG.jitter(Jitter::Decorrelated).jitter_seed(42);
+
+#[retry(BACKOFF_CONFIG, |_| true, report)]
+async fn unlimited() {}
+
+#[retry(LIMITED_CONFIG, |_| true, report)]
+async fn limited() {}
+
+#[retry(JITTERED_CONFIG, |_| true, report)]
+async fn jittered() {}
+
+fn secs(delays: impl Iterator<Item = Duration>) -> Vec<u64> {
+    delays.map(|delay| delay.as_secs()).collect()
+}
+
+#[test]
+fn test_delays() {
+    assert_eq!(vec![1, 2, 4, 8, 10, 10], secs(BACKOFF_CONFIG.delays()));
+    assert_eq!(Duration::from_secs(35), BACKOFF_CONFIG.delays().sum());
+
+    // 1s + 2s + 4s + 8s = 15s, and another 10s would exceed 20s
+    assert_eq!(vec![1, 2, 4, 8], secs(LIMITED_CONFIG.delays()));
+    assert_eq!(
+        vec![1, 2, 4, 8],
+        secs(BACKOFF_CONFIG.schedule_within(Duration::from_secs(20)))
+    );
+
+    // no retries at all
+    assert_eq!(0, BACKOFF_CONFIG.max_retries(0).delays().count());
+}
+
+#[tokio::test]
+async fn test_delays_match_calls() {
+    pause();
+
+    let ((), report) = unlimited_with_report().await;
+    assert_eq!(BACKOFF_CONFIG.delays().collect::<Vec<_>>(), report.delays);
+
+    let ((), report) = limited_with_report().await;
+    assert_eq!(LIMITED_CONFIG.delays().collect::<Vec<_>>(), report.delays);
+
+    let ((), report) = jittered_with_report().await;
+    assert_eq!(JITTERED_CONFIG.delays().collect::<Vec<_>>(), report.delays);
+}
+
+#[test]
+fn test_long_unbounded_schedule() {
+    let config = ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(100);
+
+    // the waits saturate long before the last retry, without a t_wait_max to stop at
+    let delays: Vec<_> = config.delays().collect();
+    assert_eq!(100, delays.len());
+    assert_eq!(Some(&Duration::MAX), delays.last());
+}
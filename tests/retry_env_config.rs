@@ -0,0 +1,99 @@
+//! This example tests reading configurations from environment variables, both directly with
+//! `from_env` and `with_env`, and at runtime in functions decorated with `env`.
+//!
+//! Each test sets its own variables, since tests run in parallel in the same process.
+use retry_if::{retry, ConfigError, ExponentialBackoffConfig, Jitter, RetryExt};
+use std::env;
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+
+#[retry(BACKOFF_CONFIG, |_| true, env, report)]
+async fn tuned() {}
+
+#[retry(BACKOFF_CONFIG, |_| true, env, report)]
+async fn mistuned() {}
+
+#[test]
+fn test_from_env() {
+    env::set_var("RETRY_IF_TEST_DB_MAX_RETRIES", "7");
+    env::set_var("RETRY_IF_TEST_DB_T_WAIT", "250ms");
+    env::set_var("RETRY_IF_TEST_DB_BACKOFF", "1.5");
+    env::set_var("RETRY_IF_TEST_DB_BACKOFF_MAX", "30s");
+    env::set_var("RETRY_IF_TEST_DB_T_WAIT_MAX", "90");
+    env::set_var("RETRY_IF_TEST_DB_JITTER", "Decorrelated");
+    env::set_var("RETRY_IF_TEST_DB_JITTER_SEED", "42");
+
+    let expected = ExponentialBackoffConfig::new(Duration::from_millis(250))
+        .max_retries(7)
+        .backoff(1.5)
+        .backoff_max(Duration::from_secs(30))
+        .t_wait_max(Duration::from_secs(90))
+        .jitter(Jitter::Decorrelated)
+        .jitter_seed(42);
+
+    assert_eq!(
+        Ok(expected),
+        ExponentialBackoffConfig::from_env("RETRY_IF_TEST_DB")
+    );
+
+    // without any variables set, the base configuration is returned as is
+    assert_eq!(
+        Ok(ExponentialBackoffConfig::default()),
+        ExponentialBackoffConfig::from_env("RETRY_IF_TEST_UNSET")
+    );
+    assert_eq!(
+        Ok(BACKOFF_CONFIG),
+        BACKOFF_CONFIG.with_env("RETRY_IF_TEST_UNSET")
+    );
+}
+
+#[test]
+fn test_with_env_errors() {
+    env::set_var("RETRY_IF_TEST_BAD_T_WAIT", "soon");
+    assert_eq!(
+        Err(ConfigError::InvalidEnvVar {
+            name: "RETRY_IF_TEST_BAD_T_WAIT".to_string(),
+            value: "soon".to_string(),
+        }),
+        BACKOFF_CONFIG.with_env("RETRY_IF_TEST_BAD")
+    );
+
+    // overrides are validated along with the rest of the configuration
+    env::set_var("RETRY_IF_TEST_INVALID_BACKOFF", "0.5");
+    assert_eq!(
+        Err(ConfigError::InvalidBackoff(0.5)),
+        BACKOFF_CONFIG.with_env("RETRY_IF_TEST_INVALID")
+    );
+}
+
+#[tokio::test]
+async fn test_env_overrides() {
+    pause();
+
+    env::set_var("RETRY_IF__TUNED__MAX_RETRIES", "1");
+    env::set_var("RETRY_IF__TUNED__T_WAIT", "3s");
+    let ((), report) = tuned_with_report().await;
+    assert_eq!(2, report.attempts);
+    assert_eq!(vec![Duration::from_secs(3)], report.delays);
+
+    // unparseable overrides are ignored
+    env::set_var("RETRY_IF__MISTUNED__MAX_RETRIES", "one");
+    let ((), report) = mistuned_with_report().await;
+    assert_eq!(6, report.attempts);
+}
+
+#[tokio::test]
+async fn test_retry_function_env() {
+    pause();
+
+    env::set_var("RETRY_IF_TEST_FUNCTION_MAX_RETRIES", "2");
+    let ((), report) = (async || ())
+        .retrying(BACKOFF_CONFIG, |_| true)
+        .env("RETRY_IF_TEST_FUNCTION")
+        .run_with_report()
+        .await;
+    assert_eq!(3, report.attempts);
+}
@@ -0,0 +1,105 @@
+//! This example tests that `#[retry(...)]` accepts arbitrary expressions for its arguments: paths to
+//! consts in other modules, struct literals, associated function calls and closures.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::num::TryFromIntError;
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+mod policies {
+    use retry_if::ExponentialBackoffConfig;
+    use std::time::Duration;
+
+    pub const DB_BACKOFF: ExponentialBackoffConfig =
+        ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(3);
+
+    pub fn is_err<T, E>(result: &Result<T, E>) -> bool {
+        result.is_err()
+    }
+}
+
+pub struct Counter {
+    pub count: i64,
+}
+
+impl Counter {
+    fn backoff() -> ExponentialBackoffConfig {
+        ExponentialBackoffConfig {
+            max_retries: 2,
+            t_wait: Duration::from_secs(2),
+            backoff: 1.0,
+            t_wait_max: None,
+            backoff_max: None,
+            jitter: None,
+            jitter_seed: None,
+        }
+    }
+
+    #[retry(Self::backoff(), |count| *count < 10)]
+    async fn increase_count(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[tokio::test]
+async fn test_path_arguments() {
+    #[retry(policies::DB_BACKOFF, policies::is_err)]
+    async fn method() -> Result<i64, TryFromIntError> {
+        i64::try_from(i128::MAX)
+    }
+
+    pause();
+    let start = Instant::now();
+    let result = method().await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 2s, 4s = 7s
+    assert!(result.is_err());
+    assert!(duration > Duration::from_secs(7));
+    assert!(duration < Duration::from_millis(7100));
+}
+
+#[tokio::test]
+async fn test_struct_literal_and_closure_arguments() {
+    #[retry(
+        ExponentialBackoffConfig {
+            max_retries: 4,
+            t_wait: Duration::from_secs(1),
+            backoff: 1.0,
+            t_wait_max: None,
+            backoff_max: None,
+            jitter: None,
+            jitter_seed: None,
+        },
+        |result| result.is_err()
+    )]
+    async fn method() -> Result<i64, TryFromIntError> {
+        i64::try_from(i128::MAX)
+    }
+
+    pause();
+    let start = Instant::now();
+    let result = method().await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 1s, 1s, 1s = 4s
+    assert!(result.is_err());
+    assert!(duration > Duration::from_secs(4));
+    assert!(duration < Duration::from_millis(4100));
+}
+
+#[tokio::test]
+async fn test_associated_function_argument() {
+    let mut counter = Counter { count: 0 };
+
+    pause();
+    let start = Instant::now();
+    counter.increase_count().await;
+    let duration = Instant::now() - start;
+
+    // max of 2 retries, waits of 2s, 2s = 4s
+    assert!(duration > Duration::from_secs(4));
+    assert!(duration < Duration::from_millis(4100));
+    // initial attempt + 2 retries
+    assert_eq!(3, counter.count);
+}
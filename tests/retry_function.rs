@@ -0,0 +1,221 @@
+//! This example tests retrying without the attribute, through `retry_if::retry` and the
+//! `RetryExt` extension trait, which run the same loop as `#[retry(...)]`.
+use retry_if::{
+    retry, ExponentialBackoffConfig, GiveUpContext, RetryContext, RetryDecision, RetryExt, Sleeper,
+    StopReason,
+};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+
+#[tokio::test]
+async fn test_retry_function() {
+    // the operation may borrow its surroundings mutably, since it's only called one at a time
+    let mut calls = 0;
+
+    pause();
+    let start = Instant::now();
+    let result = retry(
+        BACKOFF_CONFIG,
+        |r: &Result<u32, ()>| r.is_err(),
+        async || {
+            calls += 1;
+            if calls < 3 {
+                Err(())
+            } else {
+                Ok(calls)
+            }
+        },
+    )
+    .await;
+    let duration = Instant::now() - start;
+
+    assert_eq!(Ok(3), result);
+    assert_eq!(3, calls);
+    // 1s + 2s
+    assert!(duration >= Duration::from_millis(3000));
+    assert!(duration < Duration::from_millis(3010));
+}
+
+#[tokio::test]
+async fn test_same_as_attribute() {
+    static DECORATED_CALLS: Mutex<u32> = Mutex::new(0);
+
+    #[retry(BACKOFF_CONFIG, |r| r.is_err(), report, name = "flaky")]
+    async fn decorated() -> Result<u32, u32> {
+        let mut calls = DECORATED_CALLS.lock().unwrap();
+        *calls += 1;
+        if *calls < 4 {
+            Err(*calls)
+        } else {
+            Ok(*calls)
+        }
+    }
+
+    let mut calls = 0;
+    let flaky = async || {
+        calls += 1;
+        if calls < 4 {
+            Err(calls)
+        } else {
+            Ok(calls)
+        }
+    };
+
+    pause();
+    let expected = decorated_with_report().await;
+    let actual = flaky
+        .retrying(BACKOFF_CONFIG, Result::is_err)
+        .name("flaky")
+        .run_with_report()
+        .await;
+
+    assert_eq!(expected, actual);
+    assert_eq!(StopReason::NotRetried, actual.1.reason);
+}
+
+#[tokio::test]
+async fn test_report_failures() {
+    let mut calls = 0;
+
+    pause();
+    let (result, report) = (async || {
+        calls += 1;
+        Err::<(), u32>(calls)
+    })
+    .retrying(BACKOFF_CONFIG.max_retries(2), Result::is_err)
+    .report_failures()
+    .run_with_report()
+    .await;
+
+    assert_eq!(Err(3), result);
+    // the final result isn't a failure that was retried
+    assert_eq!(
+        Some(vec!["Err(1)".to_string(), "Err(2)".to_string()]),
+        report.failures
+    );
+}
+
+#[test]
+fn test_futures_are_send() {
+    fn assert_send(_: impl Future + Send) {}
+
+    #[retry(BACKOFF_CONFIG, |r| r.is_err(), report)]
+    async fn decorated() -> Result<(), ()> {
+        Ok(())
+    }
+
+    #[retry(
+        BACKOFF_CONFIG,
+        |r| r.is_err(),
+        on_retry = async |_, context| {
+            tokio::task::yield_now().await;
+            let _ = context.config.max_delay();
+        },
+        on_giveup = async |_, _| tokio::task::yield_now().await
+    )]
+    async fn hooked(id: u32) -> Result<u32, ()> {
+        Ok(id)
+    }
+
+    assert_send(decorated());
+    assert_send(decorated_with_report());
+    assert_send(hooked(1));
+    assert_send(retry(BACKOFF_CONFIG, |_| false, async || ()));
+    assert_send(
+        (async || ())
+            .retrying(BACKOFF_CONFIG, |_| false)
+            .name("send")
+            .run_with_report(),
+    );
+    assert_send(
+        (async || ())
+            .retrying(BACKOFF_CONFIG, |_| false)
+            .on_retry(async |_, _| tokio::task::yield_now().await)
+            .run(),
+    );
+}
+
+#[tokio::test]
+async fn test_hooks() {
+    let retried = Mutex::new(Vec::new());
+    let gave_up = Mutex::new(None);
+
+    pause();
+    let result = (async || Err::<(), &str>("unavailable"))
+        .retrying_with_context(
+            BACKOFF_CONFIG,
+            |r: &Result<(), &str>, context: &RetryContext| {
+                if context.attempt < 3 {
+                    RetryDecision::from(r.is_err())
+                } else {
+                    RetryDecision::RetryAfter(Duration::from_millis(500))
+                }
+            },
+        )
+        .on_retry(async |_: &Result<(), &str>, context: &RetryContext| {
+            retried
+                .lock()
+                .unwrap()
+                .push((context.attempt, context.delay));
+        })
+        .on_giveup(async |result: &Result<(), &str>, context: &GiveUpContext| {
+            *gave_up.lock().unwrap() = Some((*result, context.attempts, context.reason));
+        })
+        .run()
+        .await;
+
+    assert_eq!(Err("unavailable"), result);
+    assert_eq!(
+        vec![
+            (1, Some(Duration::from_secs(1))),
+            (2, Some(Duration::from_secs(2))),
+            (3, Some(Duration::from_millis(500))),
+            (4, Some(Duration::from_millis(500))),
+            (5, Some(Duration::from_millis(500))),
+        ],
+        *retried.lock().unwrap()
+    );
+    assert_eq!(
+        Some((Err("unavailable"), 6, StopReason::RetriesExhausted)),
+        *gave_up.lock().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_custom_sleeper() {
+    struct CountingSleeper {
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    impl Sleeper for CountingSleeper {
+        fn now(&self) -> std::time::Instant {
+            Instant::now().into_std()
+        }
+
+        fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+            self.sleeps.lock().unwrap().push(duration);
+            tokio::time::sleep(duration)
+        }
+    }
+
+    let sleeper = CountingSleeper {
+        sleeps: Mutex::new(Vec::new()),
+    };
+
+    pause();
+    (async || ())
+        .retrying(BACKOFF_CONFIG.max_retries(2), |_| true)
+        .sleeper(&sleeper)
+        .run()
+        .await;
+
+    assert_eq!(
+        vec![Duration::from_secs(1), Duration::from_secs(2)],
+        *sleeper.sleeps.lock().unwrap()
+    );
+}
@@ -0,0 +1,67 @@
+//! This example tests that the identifiers and labels generated by `#[retry(...)]` can't collide
+//! with those in the decorated function, which uses parameters, variables and a labeled block with
+//! the same names as the expansion.
+//!
+//! The expectation is that two retries will take 1s, 2s, for a total of 3s of execution time.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+
+pub struct Counter {
+    pub count: i64,
+}
+
+impl Counter {
+    #[retry(BACKOFF_CONFIG, |result| *result > 0 && *result < __attempt + attempt)]
+    async fn increase_count(&mut self, attempt: i64, __attempt: i64) -> i64 {
+        let result = 'block: {
+            self.count += 1;
+            if self.count > 100 {
+                // a return inside the user's own 'block must still exit the function
+                return -1;
+            }
+            break 'block self.count;
+        };
+
+        let mut start = 0;
+        'attempts: for retry_wait in 0..result {
+            if retry_wait > 0 {
+                break 'attempts;
+            }
+            start += 1000;
+        }
+
+        result + start
+    }
+}
+
+#[tokio::test]
+async fn test_hygienic_expansion() {
+    let mut counter = Counter { count: 0 };
+
+    pause();
+    let start = Instant::now();
+    let value = counter.increase_count(3, 1000).await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 2s = 3s
+    assert!(duration > Duration::from_secs(3));
+    assert!(duration < Duration::from_millis(3100));
+    assert_eq!(1003, value);
+    // initial attempt + 2 retries
+    assert_eq!(3, counter.count);
+}
+
+#[tokio::test]
+async fn test_return_inside_labeled_block() {
+    let mut counter = Counter { count: 100 };
+
+    pause();
+    let value = counter.increase_count(3, 1000).await;
+
+    assert_eq!(-1, value);
+    assert_eq!(101, counter.count);
+}
@@ -7,13 +7,10 @@ use retry_if::{retry, ExponentialBackoffConfig};
 use std::time::Duration;
 use tokio::time::{pause, Instant};
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 3.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(5)
+        .backoff(3.0);
 
 fn retry_if(i: &i64) -> bool {
     *i < 3
@@ -0,0 +1,118 @@
+//! This example tests jittered delays, checking each delay falls within the bounds of its jitter
+//! mode, and that seeded configurations wait the same delays on every call.
+//!
+//! Without jitter, the configuration waits 1s, 2s, 4s, 8s and 10s, since `backoff_max` is 10s.
+use retry_if::{retry, BackoffStrategy, ExponentialBackoffConfig, Jitter, RetryReport};
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(5)
+        .backoff_max(Duration::from_secs(10));
+
+const UNJITTERED: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(4),
+    Duration::from_secs(8),
+    Duration::from_secs(10),
+];
+
+const FULL: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    jitter: Some(Jitter::Full),
+    ..BACKOFF_CONFIG
+};
+
+const EQUAL: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    jitter: Some(Jitter::Equal),
+    ..BACKOFF_CONFIG
+};
+
+const DECORRELATED: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    jitter: Some(Jitter::Decorrelated),
+    ..BACKOFF_CONFIG
+};
+
+const SEEDED: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    jitter_seed: Some(42),
+    ..FULL
+};
+
+#[retry(FULL, |_| true, report)]
+async fn full() {}
+
+#[retry(EQUAL, |_| true, report)]
+async fn equal() {}
+
+#[retry(DECORRELATED, |_| true, report)]
+async fn decorrelated() {}
+
+#[retry(SEEDED, |_| true, report)]
+async fn seeded() {}
+
+async fn delays<F: std::future::Future<Output = ((), RetryReport)>>(f: F) -> Vec<Duration> {
+    let ((), report) = f.await;
+    assert_eq!(6, report.attempts);
+    report.delays
+}
+
+#[tokio::test]
+async fn test_full_jitter() {
+    pause();
+    let delays = delays(full_with_report()).await;
+
+    for (delay, unjittered) in delays.iter().zip(UNJITTERED) {
+        assert!(*delay <= unjittered);
+    }
+    assert_ne!(UNJITTERED.to_vec(), delays);
+}
+
+#[tokio::test]
+async fn test_equal_jitter() {
+    pause();
+    let delays = delays(equal_with_report()).await;
+
+    for (delay, unjittered) in delays.iter().zip(UNJITTERED) {
+        assert!(*delay >= unjittered / 2);
+        assert!(*delay <= unjittered);
+    }
+}
+
+#[tokio::test]
+async fn test_decorrelated_jitter() {
+    pause();
+    let delays = delays(decorrelated_with_report()).await;
+
+    // each wait is between the first delay of 1s and three times the previous wait, up to 10s
+    let mut previous = Duration::from_secs(1);
+    for delay in delays {
+        assert!(delay >= Duration::from_secs(1));
+        assert!(delay <= (previous * 3).min(Duration::from_secs(10)));
+        previous = delay;
+    }
+}
+
+#[tokio::test]
+async fn test_seeded_jitter() {
+    pause();
+
+    // seeded calls wait the same delays, while unseeded calls are seeded differently each time
+    assert_eq!(
+        delays(seeded_with_report()).await,
+        delays(seeded_with_report()).await
+    );
+    assert_ne!(
+        delays(full_with_report()).await,
+        delays(full_with_report()).await
+    );
+}
+
+#[test]
+fn test_builders_and_accessors() {
+    // the builders don't shadow the strategy's accessors, which read back what they set
+    let config = BACKOFF_CONFIG.jitter(Jitter::Equal).jitter_seed(7);
+    assert_eq!(Some(Jitter::Equal), config.jitter_mode());
+    assert_eq!(Some(7), config.rng_seed());
+    assert_eq!(None, BACKOFF_CONFIG.rng_seed());
+}
@@ -3,13 +3,8 @@ use std::num::ParseIntError;
 use std::str::FromStr;
 use std::time::Duration;
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 
 #[tokio::test]
 async fn test_retry_with_try_operator_on_result() {
@@ -31,6 +26,7 @@ async fn test_retry_with_try_operator_on_result() {
 async fn test_retry_with_try_operator_on_result_with_into() {
     // show that the ? operator semantics of auto-converting using From/Into are intact after expansion
     #[derive(Debug, PartialEq)]
+    #[allow(dead_code)]
     pub enum SomeError {
         ParseIntError,
         Other,
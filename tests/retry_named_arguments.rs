@@ -0,0 +1,50 @@
+//! This example tests the named form of `#[retry(...)]` arguments, alone and mixed with the
+//! positional form.
+//!
+//! Each decorated function retries twice, waiting 1s and 2s for a total of 3s of execution time.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2);
+
+fn retry_if(_: &()) -> bool {
+    true
+}
+
+#[retry(config = BACKOFF_CONFIG, retry_if = retry_if, name = "named")]
+async fn named() {}
+
+#[retry(retry_if = retry_if, config = BACKOFF_CONFIG)]
+async fn named_out_of_order() {}
+
+#[retry(BACKOFF_CONFIG, retry_if, name = "mixed")]
+async fn mixed() {}
+
+#[retry(BACKOFF_CONFIG, retry_if = retry_if)]
+async fn positional_config_named_retry_if() {}
+
+async fn assert_three_seconds<F: std::future::Future<Output = ()>>(f: F) {
+    let start = Instant::now();
+    f.await;
+    let duration = Instant::now() - start;
+
+    // waits of 1s, 2s = 3s
+    assert!(duration > Duration::from_secs(3));
+    assert!(duration < Duration::from_millis(3100));
+}
+
+#[tokio::test]
+async fn test_named_arguments() {
+    pause();
+    assert_three_seconds(named()).await;
+    assert_three_seconds(named_out_of_order()).await;
+}
+
+#[tokio::test]
+async fn test_mixed_arguments() {
+    pause();
+    assert_three_seconds(mixed()).await;
+    assert_three_seconds(positional_config_named_retry_if()).await;
+}
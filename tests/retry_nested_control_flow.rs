@@ -0,0 +1,77 @@
+//! This example tests that `return` and `?` are only redirected into the retry loop when they belong
+//! to the decorated function, and keep their meaning inside closures, async blocks and nested items.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2);
+
+fn retry_if<T>(result: &Result<T, ParseIntError>) -> bool {
+    result.is_err()
+}
+
+#[tokio::test]
+async fn test_try_operator_in_try_for_each() {
+    #[retry(BACKOFF_CONFIG, retry_if)]
+    async fn sum(values: &[&str]) -> Result<i32, ParseIntError> {
+        let mut total = 0;
+        values.iter().try_for_each(|value| {
+            total += i32::from_str(value)?;
+            Ok::<(), ParseIntError>(())
+        })?;
+        Ok(total)
+    }
+
+    pause();
+    assert_eq!(Ok(6), sum(&["1", "2", "3"]).await);
+    assert!(sum(&["1", "notNum", "3"]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_try_operator_and_return_in_map() {
+    #[retry(BACKOFF_CONFIG, retry_if)]
+    async fn doubled(values: &[&str]) -> Result<Vec<i32>, ParseIntError> {
+        values
+            .iter()
+            .map(|value| {
+                if value.is_empty() {
+                    return Ok(0);
+                }
+                Ok(i32::from_str(value)? * 2)
+            })
+            .collect()
+    }
+
+    pause();
+    assert_eq!(Ok(vec![2, 0, 6]), doubled(&["1", "", "3"]).await);
+    assert!(doubled(&["1", "notNum"]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_async_block_and_nested_fn() {
+    #[retry(BACKOFF_CONFIG, retry_if)]
+    async fn parse_twice(value: &str) -> Result<i32, ParseIntError> {
+        fn parse(value: &str) -> Result<i32, ParseIntError> {
+            if value.is_empty() {
+                return Ok(0);
+            }
+            let parsed = i32::from_str(value)?;
+            Ok(parsed)
+        }
+
+        let first = async {
+            let parsed = i32::from_str(value)?;
+            Ok::<_, ParseIntError>(parsed)
+        }
+        .await?;
+        let second = parse(value)?;
+        Ok(first + second)
+    }
+
+    pause();
+    assert_eq!(Ok(4), parse_twice("2").await);
+    assert!(parse_twice("notNum").await.is_err());
+}
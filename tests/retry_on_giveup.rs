@@ -0,0 +1,119 @@
+//! This example tests the `on_giveup` hook, which runs once with the final result when the retries
+//! end while `retry_if` would still retry it.
+//!
+//! Each hook records the results and contexts it's called with, so tests can check it ran once.
+use retry_if::{retry, ExponentialBackoffConfig, GiveUpContext, StopReason};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::pause;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(3);
+
+const LIMITED_BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+    max_retries: 25,
+    t_wait_max: Some(Duration::from_secs(5)),
+    ..BACKOFF_CONFIG
+};
+
+type GiveUps = Mutex<Vec<(Result<u32, u32>, GiveUpContext)>>;
+
+#[retry(
+    BACKOFF_CONFIG,
+    |r| r.is_err(),
+    on_giveup = async |r: &Result<u32, u32>, context: &GiveUpContext| {
+        give_ups.lock().unwrap().push((*r, *context));
+    }
+)]
+async fn fail_until(
+    attempts: &Mutex<u32>,
+    succeed_on: u32,
+    give_ups: &GiveUps,
+) -> Result<u32, u32> {
+    let mut attempts = attempts.lock().unwrap();
+    *attempts += 1;
+    if *attempts == succeed_on {
+        Ok(*attempts)
+    } else {
+        Err(*attempts)
+    }
+}
+
+#[retry(
+    LIMITED_BACKOFF_CONFIG,
+    |r| r.is_err(),
+    on_giveup = async |r: &Result<u32, u32>, context: &GiveUpContext| {
+        give_ups.lock().unwrap().push((*r, *context));
+    }
+)]
+async fn always_fails(give_ups: &GiveUps) -> Result<u32, u32> {
+    Err(0)
+}
+
+#[tokio::test]
+async fn test_retries_exhausted() {
+    let give_ups = GiveUps::default();
+    pause();
+    let result = fail_until(&Mutex::new(0), u32::MAX, &give_ups).await;
+
+    let give_ups = give_ups.into_inner().unwrap();
+    assert_eq!(1, give_ups.len());
+
+    let (final_result, context) = give_ups[0];
+    assert_eq!(result, final_result);
+    assert_eq!(Err(4), final_result);
+    assert_eq!(4, context.attempts);
+    assert_eq!(StopReason::RetriesExhausted, context.reason);
+
+    // waits of 1s, 2s, 4s = 7s
+    assert!(context.elapsed > Duration::from_secs(7));
+    assert!(context.elapsed < Duration::from_millis(7100));
+}
+
+#[tokio::test]
+async fn test_deadline_exceeded() {
+    let give_ups = GiveUps::default();
+    pause();
+    always_fails(&give_ups).await.unwrap_err();
+
+    let give_ups = give_ups.into_inner().unwrap();
+    assert_eq!(1, give_ups.len());
+
+    // waits of 1s, 2s = 3s, and waiting another 4s would exceed 5s
+    let (_, context) = give_ups[0];
+    assert_eq!(3, context.attempts);
+    assert_eq!(StopReason::DeadlineExceeded, context.reason);
+    assert!(context.elapsed > Duration::from_secs(3));
+    assert!(context.elapsed < Duration::from_millis(3100));
+}
+
+#[tokio::test]
+async fn test_not_called_on_success() {
+    let give_ups = GiveUps::default();
+    pause();
+    assert_eq!(Ok(1), fail_until(&Mutex::new(0), 1, &give_ups).await);
+    assert_eq!(Ok(3), fail_until(&Mutex::new(0), 3, &give_ups).await);
+
+    assert!(give_ups.into_inner().unwrap().is_empty());
+}
+
+#[test]
+fn test_sync_hook() {
+    const SYNC_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        t_wait: Duration::from_millis(1),
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(
+        SYNC_CONFIG,
+        |r| r.is_err(),
+        on_giveup = |_, context: &GiveUpContext| *given_up_after = Some(context.attempts)
+    )]
+    fn always_fails(given_up_after: &mut Option<u32>) -> Result<(), ()> {
+        Err(())
+    }
+
+    let mut given_up_after = None;
+    assert_eq!(Err(()), always_fails(&mut given_up_after));
+    assert_eq!(Some(4), given_up_after);
+}
@@ -0,0 +1,175 @@
+//! This example tests the `on_retry` hook, which runs between a failed attempt and the sleep
+//! before the next one.
+//!
+//! A client refreshes an expired token from its hook, which the next attempt then succeeds with.
+use retry_if::{retry, ExponentialBackoffConfig, RetryContext};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(3);
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Unauthorized,
+}
+
+struct Client {
+    token: Mutex<&'static str>,
+    requests: AtomicU32,
+}
+
+impl Client {
+    async fn refresh_token(&self) {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        *self.token.lock().unwrap() = "fresh";
+    }
+
+    #[retry(
+        BACKOFF_CONFIG,
+        |r| r == &Err(Error::Unauthorized),
+        on_retry = async |_, _| self.refresh_token().await
+    )]
+    async fn request(&self) -> Result<u32, Error> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        match *self.token.lock().unwrap() {
+            "fresh" => Ok(200),
+            _ => Err(Error::Unauthorized),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_hook_refreshes_state() {
+    let client = Client {
+        token: Mutex::new("expired"),
+        requests: AtomicU32::new(0),
+    };
+
+    pause();
+    let start = Instant::now();
+    let result = client.request().await;
+    let duration = Instant::now() - start;
+
+    assert_eq!(Ok(200), result);
+    assert_eq!(2, client.requests.load(Ordering::SeqCst));
+
+    // refreshing for 100ms, then waiting 1s
+    assert!(duration > Duration::from_millis(1100));
+    assert!(duration < Duration::from_millis(1200));
+}
+
+/// The result, attempt, delay and elapsed time seen by a hook.
+type Seen = (u32, (u32, Duration, Duration));
+
+#[tokio::test]
+async fn test_hook_context() {
+    #[retry(
+        BACKOFF_CONFIG,
+        |_| true,
+        on_retry = async |result: &u32, context: &RetryContext| {
+            let context = (context.attempt, context.delay.unwrap(), context.elapsed);
+            contexts.lock().unwrap().push((*result, context));
+        }
+    )]
+    async fn method(contexts: &Mutex<Vec<Seen>>) -> u32 {
+        contexts.lock().unwrap().len() as u32
+    }
+
+    let contexts = Mutex::new(Vec::new());
+    pause();
+    method(&contexts).await;
+
+    // the hook only runs before a retry, never after the final attempt
+    let contexts = contexts.into_inner().unwrap();
+    let results: Vec<_> = contexts.iter().map(|(result, _)| *result).collect();
+    let attempts: Vec<_> = contexts.iter().map(|(_, c)| c.0).collect();
+    let delays: Vec<_> = contexts.iter().map(|(_, c)| c.1.as_secs()).collect();
+    let elapsed: Vec<_> = contexts.iter().map(|(_, c)| c.2.as_secs()).collect();
+
+    assert_eq!(vec![0, 1, 2], results);
+    assert_eq!(vec![1, 2, 3], attempts);
+    assert_eq!(vec![1, 2, 4], delays);
+    assert_eq!(vec![0, 1, 3], elapsed);
+}
+
+static HOOK_CALLS: AtomicU32 = AtomicU32::new(0);
+
+async fn count_hook_calls(_: &Result<(), ()>, _: &RetryContext<'_>) {
+    HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[retry(BACKOFF_CONFIG, |r| r.is_err(), on_retry = count_hook_calls)]
+async fn always_fails() -> Result<(), ()> {
+    Err(())
+}
+
+#[tokio::test]
+async fn test_hook_function() {
+    pause();
+    assert_eq!(Err(()), always_fails().await);
+    assert_eq!(3, HOOK_CALLS.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_sync_hook() {
+    const SYNC_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        t_wait: Duration::from_millis(1),
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(SYNC_CONFIG, |r| r.is_err(), on_retry = |_, _| *attempts_left -= 1)]
+    fn count_down(attempts_left: &mut u32) -> Result<(), u32> {
+        match *attempts_left {
+            0 => Ok(()),
+            left => Err(left),
+        }
+    }
+
+    let mut attempts_left = 2;
+    assert_eq!(Ok(()), count_down(&mut attempts_left));
+    assert_eq!(0, attempts_left);
+}
+
+#[tokio::test]
+async fn test_hook_on_mut_self() {
+    struct Session {
+        token: &'static str,
+        requests: u32,
+    }
+
+    impl Session {
+        async fn refresh(&mut self) {
+            self.token = "fresh";
+        }
+
+        // the body, the predicate and the hook each borrow `self` in turn
+        #[retry(
+            BACKOFF_CONFIG,
+            |r| self.retryable(r),
+            on_retry = async |_, _| self.refresh().await
+        )]
+        async fn call(&mut self) -> Result<u32, Error> {
+            self.requests += 1;
+            match self.token {
+                "fresh" => Ok(self.requests),
+                _ => Err(Error::Unauthorized),
+            }
+        }
+
+        fn retryable(&self, result: &Result<u32, Error>) -> bool {
+            result.is_err() && self.requests < 3
+        }
+    }
+
+    let mut session = Session {
+        token: "expired",
+        requests: 0,
+    };
+
+    pause();
+    assert_eq!(Ok(2), session.call().await);
+    assert_eq!("fresh", session.token);
+}
@@ -0,0 +1,106 @@
+//! This example tests the `<fn>_with_report` companion generated with `report`, which returns a
+//! `RetryReport` of the retries alongside the result.
+//!
+//! Each attempt of `Service::call` takes 100ms, and fails until the third attempt.
+use retry_if::{retry, ExponentialBackoffConfig, RetryReport, StopReason};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::time::{pause, sleep};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
+
+struct Service {
+    calls: AtomicU32,
+}
+
+impl Service {
+    #[retry(BACKOFF_CONFIG, |r| r.is_err(), report, report_failures)]
+    async fn call(&self) -> Result<u32, String> {
+        sleep(Duration::from_millis(100)).await;
+        match self.calls.fetch_add(1, Ordering::SeqCst) + 1 {
+            attempt @ 3.. => Ok(attempt),
+            attempt => Err(format!("attempt {attempt} failed")),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_report() {
+    let service = Service {
+        calls: AtomicU32::new(0),
+    };
+
+    pause();
+    let (result, report) = service.call_with_report().await;
+
+    assert_eq!(Ok(3), result);
+    assert_eq!(3, report.attempts);
+    assert_eq!(StopReason::NotRetried, report.reason);
+    assert_eq!(
+        vec![Duration::from_secs(1), Duration::from_secs(2)],
+        report.delays
+    );
+    assert_eq!(3, report.attempt_durations.len());
+    for duration in &report.attempt_durations {
+        assert!(*duration >= Duration::from_millis(100));
+        assert!(*duration < Duration::from_millis(110));
+    }
+
+    // attempts of 100ms each, with waits of 1s, 2s = 3.3s
+    assert!(report.elapsed >= Duration::from_millis(3300));
+    assert!(report.elapsed < Duration::from_millis(3310));
+
+    assert_eq!(
+        Some(vec![
+            "Err(\"attempt 1 failed\")".to_string(),
+            "Err(\"attempt 2 failed\")".to_string(),
+        ]),
+        report.failures
+    );
+
+    // the decorated function is still there, and unaffected
+    assert_eq!(Ok(4), service.call().await);
+}
+
+#[tokio::test]
+async fn test_report_retries_exhausted() {
+    const SHORT_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        max_retries: 1,
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(SHORT_CONFIG, |_| true, report)]
+    async fn always_retried() -> u32 {
+        42
+    }
+
+    pause();
+    let (result, report): (u32, RetryReport) = always_retried_with_report().await;
+
+    assert_eq!(42, result);
+    assert_eq!(2, report.attempts);
+    assert_eq!(StopReason::RetriesExhausted, report.reason);
+    assert_eq!(None, report.failures);
+}
+
+#[test]
+fn test_sync_report() {
+    const SYNC_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        max_retries: 2,
+        t_wait: Duration::from_millis(1),
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(SYNC_CONFIG, |_| true, report)]
+    fn always_retried() {}
+
+    let ((), report) = always_retried_with_report();
+
+    assert_eq!(3, report.attempts);
+    assert_eq!(
+        vec![Duration::from_millis(1), Duration::from_millis(2)],
+        report.delays
+    );
+    assert_eq!(StopReason::RetriesExhausted, report.reason);
+}
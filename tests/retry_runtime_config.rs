@@ -0,0 +1,124 @@
+//! This example tests configurations that are only known at runtime: fields of `self`,
+//! arguments, and `LazyLock`, `OnceLock` and `Mutex` statics.
+//!
+//! Every function here always retries, so each call waits out its whole schedule.
+use retry_if::{retry, ExponentialBackoffConfig, FixedScheduleConfig, RetryReport};
+use std::borrow::Cow;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::pause;
+
+struct Client {
+    retry_config: ExponentialBackoffConfig,
+    schedule: FixedScheduleConfig,
+    calls: u32,
+}
+
+impl Client {
+    #[retry(config = self.retry_config, retry_if = |_| true, report)]
+    async fn call(&self) {}
+
+    // the configuration is copied out of `self`, so the body can still borrow it mutably
+    #[retry(config = self.retry_config, retry_if = |_| true, report)]
+    async fn call_mut(&mut self) {
+        self.calls += 1;
+    }
+
+    // `FixedScheduleConfig` isn't `Copy`, so it's borrowed instead
+    #[retry(config = &self.schedule, retry_if = |_| true, report)]
+    async fn scheduled(&self) {}
+}
+
+static LAZY_CONFIG: LazyLock<ExponentialBackoffConfig> = LazyLock::new(|| {
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(2)
+        .backoff(3.0)
+});
+
+static ONCE_CONFIG: OnceLock<ExponentialBackoffConfig> = OnceLock::new();
+
+static SHARED_CONFIG: Mutex<ExponentialBackoffConfig> =
+    Mutex::new(ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(3));
+
+#[retry(*LAZY_CONFIG, |_| true, report)]
+async fn lazy() {}
+
+#[retry(ONCE_CONFIG.get_or_init(|| ExponentialBackoffConfig::new(Duration::from_secs(2))), |_| true, report)]
+async fn once() {}
+
+#[retry(config, |_| true, report)]
+async fn from_argument(config: ExponentialBackoffConfig) {
+    let _ = config;
+}
+
+// after the first retry, the shared configuration is replaced, which only later calls see
+#[retry(
+    *SHARED_CONFIG.lock().unwrap(),
+    |_| true,
+    on_retry = async |_, _| {
+        *SHARED_CONFIG.lock().unwrap() = ExponentialBackoffConfig::new(Duration::from_secs(5));
+    },
+    report
+)]
+async fn shared() {}
+
+fn delays(report: RetryReport) -> Vec<u64> {
+    report.delays.iter().map(Duration::as_secs).collect()
+}
+
+#[tokio::test]
+async fn test_config_from_self() {
+    pause();
+    let mut client = Client {
+        retry_config: ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2),
+        schedule: FixedScheduleConfig {
+            delays: Cow::Owned(vec![Duration::from_secs(3), Duration::from_secs(1)]),
+            t_wait_max: None,
+            jitter: None,
+            jitter_seed: None,
+        },
+        calls: 0,
+    };
+
+    let ((), report) = client.call_with_report().await;
+    assert_eq!(vec![1, 2], delays(report));
+
+    let ((), report) = client.call_mut_with_report().await;
+    assert_eq!(vec![1, 2], delays(report));
+    assert_eq!(3, client.calls);
+
+    let ((), report) = client.scheduled_with_report().await;
+    assert_eq!(vec![3, 1], delays(report));
+
+    // the field is read on every call
+    client.retry_config = client.retry_config.max_retries(1);
+    let ((), report) = client.call_with_report().await;
+    assert_eq!(vec![1], delays(report));
+}
+
+#[tokio::test]
+async fn test_config_from_statics() {
+    pause();
+
+    let ((), report) = lazy_with_report().await;
+    assert_eq!(vec![1, 3], delays(report));
+
+    let ((), report) = once_with_report().await;
+    assert_eq!(vec![2, 4, 8], delays(report));
+
+    let config = ExponentialBackoffConfig::new(Duration::from_secs(2)).max_retries(1);
+    let ((), report) = from_argument_with_report(config).await;
+    assert_eq!(vec![2], delays(report));
+}
+
+#[tokio::test]
+async fn test_config_read_once_per_call() {
+    pause();
+
+    // the change made during the first call doesn't affect its remaining retries
+    let ((), report) = shared_with_report().await;
+    assert_eq!(vec![1, 2, 4], delays(report));
+
+    let ((), report) = shared_with_report().await;
+    assert_eq!(vec![5, 10, 20], delays(report));
+}
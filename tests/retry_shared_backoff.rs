@@ -0,0 +1,90 @@
+//! This example tests `SharedBackoff`, replacing the strategy while a call is still retrying.
+//!
+//! The function always retries, starting with waits of 1s, 2s, 4s, etc.
+use retry_if::{retry, BackoffStrategy, ExponentialBackoffConfig, SharedBackoff};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::time::{pause, sleep};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(4);
+
+#[retry(&backoff, |_| true, report)]
+async fn retried(backoff: SharedBackoff) {
+    let _ = backoff;
+}
+
+#[tokio::test]
+async fn test_update_in_flight() {
+    pause();
+    let backoff = SharedBackoff::new(BACKOFF_CONFIG);
+
+    // the third attempt starts at 3s, after the new configuration is stored at 2.5s
+    let updater = backoff.clone();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(2500)).await;
+        updater.store(ExponentialBackoffConfig::new(Duration::from_secs(10)).backoff(1.0));
+    });
+
+    let ((), report) = retried_with_report(backoff.clone()).await;
+
+    // the first two waits are from the original configuration, and the rest from the new one,
+    //  which only has 3 retries
+    assert_eq!(
+        vec![
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+            Duration::from_secs(10),
+        ],
+        report.delays
+    );
+}
+
+#[test]
+fn test_update() {
+    let backoff = SharedBackoff::new(BACKOFF_CONFIG);
+    let clone = backoff.clone();
+
+    clone.update(|config| config.max_retries(8));
+
+    assert_eq!(8, backoff.load().max_retries);
+    assert_eq!(BACKOFF_CONFIG.max_retries(8), *backoff.load());
+}
+
+/// A policy that replaces itself in [SWITCHING] as soon as its delay is read, with one capping
+/// every wait at 1s.
+#[derive(Debug)]
+struct Switching {
+    max_delay: Duration,
+}
+
+static SWITCHING: LazyLock<SharedBackoff<Switching>> = LazyLock::new(|| {
+    SharedBackoff::new(Switching {
+        max_delay: Duration::from_secs(20),
+    })
+});
+
+impl BackoffStrategy for Switching {
+    fn delay(&self, attempt: u32, _elapsed: Duration) -> Option<Duration> {
+        SWITCHING.store(Switching {
+            max_delay: Duration::from_secs(1),
+        });
+        (attempt == 1).then_some(Duration::from_secs(10))
+    }
+
+    fn max_delay(&self) -> Option<Duration> {
+        Some(self.max_delay)
+    }
+}
+
+#[tokio::test]
+async fn test_update_mid_decision() {
+    #[retry(&*SWITCHING, |_| true, report)]
+    async fn switched() {}
+
+    pause();
+    let ((), report) = switched_with_report().await;
+
+    // the 10s delay is capped by the policy it came from, not the one stored while deciding
+    assert_eq!(vec![Duration::from_secs(10)], report.delays);
+}
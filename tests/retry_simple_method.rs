@@ -4,13 +4,8 @@ use retry_if::{retry, ExponentialBackoffConfig};
 use std::time::Duration;
 use tokio::time::Instant;
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 
 #[tokio::test]
 async fn test_retry_if() {
@@ -1,17 +1,15 @@
-//! This example tests a backoff configuration with an exponent of 1.0, thus a linear backoff.
+//! This example tests a backoff configuration with an exponent of 1.0, thus a constant backoff.
+//! For waits that grow by a fixed step, see `LinearBackoffConfig` in retry_strategies.rs.
 //!
 //! A max of 5 tries should take 15s in total.
 use retry_if::{retry, ExponentialBackoffConfig};
 use std::time::Duration;
 use tokio::time::{pause, Instant};
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(3),
-    backoff: 1.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(3))
+        .max_retries(5)
+        .backoff(1.0);
 
 #[tokio::test]
 async fn test_retry_if() {
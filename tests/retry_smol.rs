@@ -0,0 +1,34 @@
+//! This example tests retrying on the smol runtime, and only runs with the `smol` feature.
+#![cfg(feature = "smol")]
+use retry_if::{retry, ExponentialBackoffConfig, SmolSleeper};
+use std::time::{Duration, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_millis(10)).max_retries(3);
+
+#[test]
+fn test_smol_sleeper() {
+    #[retry(BACKOFF_CONFIG, |_| true, sleeper = SmolSleeper)]
+    async fn method() {}
+
+    let start = Instant::now();
+    smol::block_on(method());
+    let duration = Instant::now() - start;
+
+    // max of 3 retries, waits of 10ms, 20ms, 40ms = 70ms
+    assert!(duration >= Duration::from_millis(70));
+    assert!(duration < Duration::from_millis(1000));
+}
+
+#[test]
+fn test_default_sleeper_outside_tokio() {
+    #[retry(BACKOFF_CONFIG, |_| true)]
+    async fn method() {}
+
+    let start = Instant::now();
+    smol::block_on(method());
+    let duration = Instant::now() - start;
+
+    assert!(duration >= Duration::from_millis(70));
+    assert!(duration < Duration::from_millis(1000));
+}
@@ -0,0 +1,116 @@
+//! This example tests the built-in strategies besides `ExponentialBackoffConfig`, each decorating a
+//! function that always retries.
+//!
+//! The total time of each call is the sum of the waits in the strategy's schedule.
+use retry_if::{
+    retry, BackoffStrategy, FibonacciBackoffConfig, FixedScheduleConfig, LinearBackoffConfig,
+};
+use std::borrow::Cow;
+use std::time::Duration;
+use tokio::time::{pause, Instant};
+
+const LINEAR_CONFIG: LinearBackoffConfig = LinearBackoffConfig {
+    max_retries: 4,
+    t_wait: Duration::from_secs(1),
+    step: Duration::from_secs(2),
+    t_wait_max: None,
+    backoff_max: None,
+    jitter: None,
+    jitter_seed: None,
+};
+
+const FIBONACCI_CONFIG: FibonacciBackoffConfig = FibonacciBackoffConfig {
+    max_retries: 6,
+    t_wait: Duration::from_secs(1),
+    t_wait_max: None,
+    backoff_max: Some(Duration::from_secs(5)),
+    jitter: None,
+    jitter_seed: None,
+};
+
+const SCHEDULE_CONFIG: FixedScheduleConfig = FixedScheduleConfig {
+    delays: Cow::Borrowed(&[
+        Duration::from_millis(100),
+        Duration::from_millis(500),
+        Duration::from_secs(2),
+        Duration::from_secs(10),
+    ]),
+    t_wait_max: None,
+    jitter: None,
+    jitter_seed: None,
+};
+
+#[retry(LINEAR_CONFIG, |_| true)]
+async fn linear() {}
+
+#[retry(FIBONACCI_CONFIG, |_| true)]
+async fn fibonacci() {}
+
+#[retry(SCHEDULE_CONFIG, |_| true)]
+async fn scheduled() {}
+
+fn delays<S: BackoffStrategy>(strategy: &S) -> Vec<Duration> {
+    (1..)
+        .map_while(|attempt| strategy.delay(attempt, Duration::ZERO))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_linear() {
+    pause();
+
+    // waits of 1s, 3s, 5s, 7s = 16s
+    let start = Instant::now();
+    linear().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(16));
+    assert!(duration < Duration::from_millis(16_100));
+}
+
+#[tokio::test]
+async fn test_fibonacci() {
+    pause();
+
+    // waits of 1s, 1s, 2s, 3s, 5s, then 8s capped to 5s = 17s
+    let start = Instant::now();
+    fibonacci().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_secs(17));
+    assert!(duration < Duration::from_millis(17_100));
+}
+
+#[tokio::test]
+async fn test_fixed_schedule() {
+    pause();
+
+    // waits of 100ms, 500ms, 2s, 10s = 12.6s
+    let start = Instant::now();
+    scheduled().await;
+    let duration = Instant::now() - start;
+    assert!(duration >= Duration::from_millis(12_600));
+    assert!(duration < Duration::from_millis(12_700));
+}
+
+#[test]
+fn test_delays() {
+    let seconds =
+        |secs: &[u64]| -> Vec<Duration> { secs.iter().copied().map(Duration::from_secs).collect() };
+
+    assert_eq!(seconds(&[1, 3, 5, 7]), delays(&LINEAR_CONFIG));
+    assert_eq!(seconds(&[1, 1, 2, 3, 5, 5]), delays(&FIBONACCI_CONFIG));
+    assert_eq!(SCHEDULE_CONFIG.delays.to_vec(), delays(&SCHEDULE_CONFIG));
+}
+
+#[test]
+fn test_fibonacci_long_schedule() {
+    let config = FibonacciBackoffConfig {
+        max_retries: i32::MAX,
+        ..FIBONACCI_CONFIG
+    };
+
+    // the sequence saturates long before this, so it isn't stepped through once per retry
+    assert_eq!(
+        Some(Duration::from_secs(5)),
+        config.delay(i32::MAX as u32, Duration::ZERO)
+    );
+}
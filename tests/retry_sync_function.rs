@@ -0,0 +1,85 @@
+//! This example tests decorating synchronous functions, which sleep by blocking the current thread
+//! rather than awaiting the runtime.
+//!
+//! Since these sleep in real time, the configurations use short waits of milliseconds.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_millis(10)).max_retries(3);
+
+const LIMITED_BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_millis(10))
+        .max_retries(25)
+        .t_wait_max(Duration::from_millis(100))
+        .backoff_max(Duration::from_millis(25));
+
+fn retry_if(_i: &i64) -> bool {
+    true
+}
+
+pub struct Counter {
+    pub count: i64,
+}
+
+impl Counter {
+    #[retry(BACKOFF_CONFIG, retry_if)]
+    fn increase_count(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+
+    #[retry(LIMITED_BACKOFF_CONFIG, retry_if)]
+    fn increase_count_limited(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[test]
+fn test_sync_method() {
+    let mut counter = Counter { count: 0 };
+
+    let start = Instant::now();
+    counter.increase_count();
+    let duration = Instant::now() - start;
+
+    // max of 3 retries, waits of 10ms, 20ms, 40ms = 70ms
+    assert!(duration >= Duration::from_millis(70));
+    assert!(duration < Duration::from_millis(1000));
+    // initial attempt + 3 retries
+    assert_eq!(4, counter.count);
+}
+
+#[test]
+fn test_sync_method_with_limits() {
+    let mut counter = Counter { count: 0 };
+
+    let start = Instant::now();
+    counter.increase_count_limited();
+    let duration = Instant::now() - start;
+
+    // waits of 10ms, 20ms, 25ms, 25ms = 80ms, then another 25ms would exceed 100ms so it exits.
+    //  Real sleeps can overrun, which may leave room for fewer waits, so check the waits that were
+    //  made were slept, and that the next one would have crossed the deadline
+    let delays = [10, 20, 25, 25, 25].map(Duration::from_millis);
+    let retries = counter.count as usize - 1;
+    assert!(retries <= 4);
+    assert!(duration >= delays[..retries].iter().sum());
+    assert!(duration + delays[retries] > Duration::from_millis(100));
+    assert!(duration < Duration::from_millis(200));
+}
+
+#[test]
+fn test_sync_function_with_try_operator() {
+    #[retry(BACKOFF_CONFIG, |r| r.is_err())]
+    fn method(int: &str) -> Result<i32, ParseIntError> {
+        let parsed = i32::from_str(int)?;
+        Ok(parsed * 2)
+    }
+
+    assert_eq!(Ok(6), method("3"));
+    assert!(method("notNum").is_err());
+}
@@ -8,13 +8,8 @@ use tokio::time::{pause, Instant};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::{fmt, Registry};
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 5,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: None,
-    backoff_max: None,
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(5);
 
 fn retry_if(_i: &i64) -> bool {
     true
@@ -44,8 +39,10 @@ async fn main() {
     let duration = end - start;
 
     // max of 5 retries, waits of 1s, 2s, 4s, 8s, 16s = 31s
-    assert!(duration > Duration::from_secs(31));
-    assert!(duration < Duration::from_millis(31100));
+    let expected: Duration = BACKOFF_CONFIG.delays().sum();
+    assert_eq!(Duration::from_secs(31), expected);
+    assert!(duration > expected);
+    assert!(duration < expected + Duration::from_millis(100));
     // initial attempt + 5 retries
     assert_eq!(6, counter.count);
 }
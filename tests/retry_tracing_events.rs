@@ -0,0 +1,287 @@
+//! This example tests the spans and events emitted by `#[retry(...)]` with the `tracing` feature,
+//! by recording them with a subscriber rather than printing them.
+//!
+//! Decorated code logs through retry-if's re-export of tracing, so this file never refers to the
+//! `tracing` crate outside of setting up the subscriber.
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::pause;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1)).max_retries(2);
+
+/// The fields of each recorded event or span, rendered with `Debug`.
+///
+/// Events and spans also record their level under `level`, and spans record their name under
+/// `span`, along with the fields recorded after the span was created.
+type Fields = BTreeMap<String, String>;
+
+#[derive(Default)]
+struct Recorded {
+    events: Vec<Fields>,
+    spans: BTreeMap<u64, Fields>,
+}
+
+impl Recorded {
+    fn messages(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .map(|event| event["message"].as_str())
+            .collect()
+    }
+
+    fn spans(&self, name: &str) -> Vec<&Fields> {
+        self.spans
+            .values()
+            .filter(|span| span["span"] == name)
+            .collect()
+    }
+}
+
+struct RecordingLayer {
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for RecordingLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut fields = Fields::new();
+        fields.insert("span".to_string(), attrs.metadata().name().to_string());
+        fields.insert("level".to_string(), attrs.metadata().level().to_string());
+        attrs.record(&mut FieldVisitor(&mut fields));
+        self.recorded
+            .lock()
+            .unwrap()
+            .spans
+            .insert(id.into_u64(), fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut recorded = self.recorded.lock().unwrap();
+        let fields = recorded.spans.get_mut(&id.into_u64()).unwrap();
+        values.record(&mut FieldVisitor(fields));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = Fields::new();
+        fields.insert("level".to_string(), event.metadata().level().to_string());
+        event.record(&mut FieldVisitor(&mut fields));
+        self.recorded.lock().unwrap().events.push(fields);
+    }
+}
+
+fn record() -> (Arc<Mutex<Recorded>>, tracing::subscriber::DefaultGuard) {
+    let recorded = Arc::new(Mutex::new(Recorded::default()));
+    let layer = RecordingLayer {
+        recorded: recorded.clone(),
+    };
+    let guard = tracing::subscriber::set_default(Registry::default().with(layer));
+    (recorded, guard)
+}
+
+#[tokio::test]
+async fn test_sleep_events() {
+    #[retry(BACKOFF_CONFIG, |_| true, name = "always_retried")]
+    async fn method() {}
+
+    let (recorded, _guard) = record();
+    pause();
+    method().await;
+
+    let recorded = recorded.lock().unwrap();
+    assert_eq!(
+        vec![
+            "Sleeping 1s on attempt 1",
+            "Sleeping 2s on attempt 2",
+            "Stopped after 3 attempts: max_retries exhausted",
+        ],
+        recorded.messages()
+    );
+    assert!(recorded
+        .events
+        .iter()
+        .all(|event| event["name"] == "\"always_retried\"" && event["level"] == "INFO"));
+    assert_eq!("max_retries exhausted", recorded.events[2]["reason"]);
+    assert_eq!("3", recorded.events[2]["attempts"]);
+}
+
+#[tokio::test]
+async fn test_stop_reasons() {
+    const DEADLINE_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
+        t_wait_max: Some(Duration::from_secs(2)),
+        ..BACKOFF_CONFIG
+    };
+
+    #[retry(BACKOFF_CONFIG, |_| false)]
+    async fn succeeds() {}
+
+    #[retry(DEADLINE_CONFIG, |_| true)]
+    async fn runs_out_of_time() {}
+
+    let (recorded, _guard) = record();
+    pause();
+    succeeds().await;
+    runs_out_of_time().await;
+
+    let recorded = recorded.lock().unwrap();
+    assert_eq!(
+        vec![
+            "Stopped after 1 attempts: not retried",
+            "Sleeping 1s on attempt 1",
+            "Stopped after 2 attempts: t_wait_max would be exceeded",
+        ],
+        recorded.messages()
+    );
+}
+
+#[tokio::test]
+async fn test_attempt_spans() {
+    #[retry(BACKOFF_CONFIG, |_| true)]
+    async fn method() {}
+
+    let (recorded, _guard) = record();
+    pause();
+    method().await;
+
+    let recorded = recorded.lock().unwrap();
+    assert_eq!(1, recorded.spans("retry").len());
+
+    let attempts = recorded.spans("attempt");
+    let numbers: Vec<_> = attempts.iter().map(|span| &span["attempt"]).collect();
+    let elapsed: Vec<_> = attempts.iter().map(|span| &span["elapsed"]).collect();
+    let delays: Vec<_> = attempts.iter().map(|span| span.get("delay")).collect();
+
+    assert_eq!(vec!["1", "2", "3"], numbers);
+    // tokio's paused clock advances a little past each sleep
+    assert_eq!("0ns", elapsed[0]);
+    assert!(elapsed[1].starts_with("1.00"));
+    assert!(elapsed[2].starts_with("3.00"));
+    assert_eq!(
+        vec![Some(&"1s".to_string()), Some(&"2s".to_string()), None],
+        delays
+    );
+}
+
+#[test]
+fn test_level() {
+    #[retry(BACKOFF_CONFIG, |_| false, level = "debug")]
+    fn method() {}
+
+    let (recorded, _guard) = record();
+    method();
+
+    let recorded = recorded.lock().unwrap();
+    assert_eq!("DEBUG", recorded.events[0]["level"]);
+    assert!(recorded.spans.values().all(|span| span["level"] == "DEBUG"));
+}
+
+#[test]
+fn test_record_args() {
+    #[derive(Debug)]
+    struct Request {
+        id: u32,
+    }
+
+    #[retry(BACKOFF_CONFIG, |_| false, record_args, skip(body), redact(token))]
+    fn send(request: &Request, token: &str, body: &[u8]) -> u32 {
+        let _ = (token, body);
+        request.id
+    }
+
+    #[retry(BACKOFF_CONFIG, |_| false)]
+    fn unrecorded(request: &Request) -> u32 {
+        request.id
+    }
+
+    let (recorded, _guard) = record();
+    send(&Request { id: 1 }, "secret", b"payload");
+    unrecorded(&Request { id: 2 });
+
+    let recorded = recorded.lock().unwrap();
+    let calls = recorded.spans("retry");
+    assert_eq!("Request { id: 1 }", calls[0]["request"]);
+    assert_eq!("\"<redacted>\"", calls[0]["token"]);
+    assert!(!calls[0].contains_key("body"));
+    assert!(!calls[1].contains_key("request"));
+}
+
+#[tokio::test]
+async fn test_retry_function() {
+    use retry_if::RetryExt;
+
+    #[retry(BACKOFF_CONFIG, |r: &Result<(), ()>| r.is_err(), name = "method")]
+    async fn method() -> Result<(), ()> {
+        Err(())
+    }
+
+    // the function traces the same spans and events as the attribute
+    let (decorated, guard) = record();
+    pause();
+    let _ = method().await;
+    drop(guard);
+
+    let (function, _guard) = record();
+    let _ = (async || Err::<(), ()>(()))
+        .retrying(BACKOFF_CONFIG, Result::is_err)
+        .name("method")
+        .run()
+        .await;
+
+    let decorated = decorated.lock().unwrap();
+    let function = function.lock().unwrap();
+    assert_eq!(decorated.events, function.events);
+    assert_eq!(
+        decorated.spans.values().collect::<Vec<_>>(),
+        function.spans.values().collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_retry_function_level() {
+    use retry_if::RetryExt;
+
+    #[retry(BACKOFF_CONFIG, |_| true, level = "debug", name = "method")]
+    async fn method() {}
+
+    let (decorated, guard) = record();
+    pause();
+    method().await;
+    drop(guard);
+
+    let (function, _guard) = record();
+    (async || ())
+        .retrying(BACKOFF_CONFIG, |_| true)
+        .name("method")
+        .level(tracing::Level::DEBUG)
+        .run()
+        .await;
+
+    let decorated = decorated.lock().unwrap();
+    let function = function.lock().unwrap();
+    assert!(decorated
+        .events
+        .iter()
+        .all(|event| event["level"] == "DEBUG"));
+    assert_eq!(decorated.events, function.events);
+    assert_eq!(
+        decorated.spans.values().collect::<Vec<_>>(),
+        function.spans.values().collect::<Vec<_>>()
+    );
+}
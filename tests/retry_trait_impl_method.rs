@@ -8,13 +8,11 @@ use retry_if::{retry, ExponentialBackoffConfig};
 use std::time::Duration;
 use tokio::time::{pause, Instant};
 
-const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig {
-    max_retries: 25,
-    t_wait: Duration::from_secs(1),
-    backoff: 2.0,
-    t_wait_max: Some(Duration::from_secs(10)),
-    backoff_max: Some(Duration::from_millis(2500)),
-};
+const BACKOFF_CONFIG: ExponentialBackoffConfig =
+    ExponentialBackoffConfig::new(Duration::from_secs(1))
+        .max_retries(25)
+        .t_wait_max(Duration::from_secs(10))
+        .backoff_max(Duration::from_millis(2500));
 
 fn retry_if(_i: &i64) -> bool {
     true
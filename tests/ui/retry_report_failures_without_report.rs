@@ -0,0 +1,14 @@
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::time::Duration;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig::new(Duration::from_secs(1))
+    .max_retries(5);
+
+fn retry_if(_: &()) -> bool {
+    true
+}
+
+#[retry(BACKOFF_CONFIG, retry_if, report_failures)]
+async fn some_method() -> () {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use retry_if::{retry, ExponentialBackoffConfig};
+use std::time::Duration;
+
+const BACKOFF_CONFIG: ExponentialBackoffConfig = ExponentialBackoffConfig::new(Duration::from_secs(1))
+    .max_retries(5);
+
+fn retry_if(_: &()) -> bool {
+    true
+}
+
+#[retry(BACKOFF_CONFIG, retry_if, skip(password))]
+async fn some_method(password: &str) -> () {
+    let _ = password;
+}
+
+fn main() {}